@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ntru::ConvPoly;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(poly) = ConvPoly::from_be_bytes(data) {
+        // Whatever decodes should encode back to the same bytes
+        assert_eq!(poly.to_be_bytes(), data);
+    }
+});
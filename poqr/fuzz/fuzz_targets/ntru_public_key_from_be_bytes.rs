@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ntru::NtruPublicKey;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(key) = NtruPublicKey::from_be_bytes(data) {
+        // A malformed key must still be usable for encryption without panicking
+        key.encrypt_bytes(b"poqr".to_vec());
+    }
+});
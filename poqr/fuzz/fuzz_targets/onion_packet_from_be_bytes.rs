@@ -0,0 +1,22 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ntru::{NtruKeyPair, NtruPrivateKey};
+use onion::OnionPacket;
+use rsa_ext::RsaPrivateKey;
+use std::sync::OnceLock;
+
+/// Key generation is slow, so every run shares one identity key and one onion key
+fn keys() -> &'static (NtruPrivateKey, Vec<RsaPrivateKey>) {
+    static KEYS: OnceLock<(NtruPrivateKey, Vec<RsaPrivateKey>)> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        let id_key = NtruKeyPair::new().private;
+        let onion_key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        (id_key, vec![onion_key])
+    })
+}
+
+fuzz_target!(|data: &[u8]| {
+    let (id_key, onion_keys) = keys();
+    let _ = OnionPacket::from_be_bytes(data, id_key.clone(), onion_keys.clone());
+});
@@ -0,0 +1,169 @@
+//! Transfers a file over TCP with every chunk encrypted under the receiver's NTRU public key.
+//!
+//! Usage:
+//!     file_transfer receive <port> <output file>
+//!     file_transfer send <address:port> <input file>
+//!
+//! The receiver sends its public key, the sender replies with a manifest (file size and SHA-256
+//! hash) and then the file in chunks, and the receiver checks both once every byte has arrived.
+//! Every message on the wire is a 4-byte big-endian length followed by that many bytes.
+use ntru::{ConvPoly, NtruKeyPair, NtruPublicKey};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+/// Plaintext bytes that fit in one NTRU message: each byte takes 5 coefficients out of N
+const MAX_PLAINTEXT: usize = ntru::params::N / 5;
+/// File bytes per chunk; each file byte is sent as two plaintext bytes (see `encode`)
+const CHUNK_SIZE: usize = MAX_PLAINTEXT / 2;
+/// Manifest layout: 8-byte file size followed by the 32-byte SHA-256 hash
+const MANIFEST_LEN: usize = 8 + 32;
+/// Largest frame either side will read: a public key or ciphertext has at most N 4-byte coefficients
+const MAX_FRAME_LEN: usize = ntru::params::N * 4;
+/// Largest file the receiver will accept, so a bogus manifest can't make it allocate without bound
+const MAX_FILE_SIZE: u64 = 1 << 30;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let result = match args.iter().map(String::as_str).collect::<Vec<_>>()[1..] {
+        ["receive", port, output] => receive(port, output),
+        ["send", addr, input] => send(addr, input),
+        _ => {
+            eprintln!("usage: file_transfer receive <port> <output file>");
+            eprintln!("       file_transfer send <address:port> <input file>");
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("file_transfer: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn receive(port: &str, output: &str) -> io::Result<()> {
+    let listener = TcpListener::bind(format!("127.0.0.1:{port}"))?;
+    println!("Waiting for a sender on port {port}...");
+    let (mut stream, addr) = listener.accept()?;
+    println!("Sender connected from {addr}");
+
+    // Hand the sender our public key so it can encrypt everything it sends
+    let keypair = NtruKeyPair::new();
+    write_frame(&mut stream, &keypair.public.to_be_bytes())?;
+
+    let manifest = recv_encrypted(&mut stream, &keypair)?;
+    if manifest.len() != MANIFEST_LEN {
+        return Err(invalid_data("malformed manifest"));
+    }
+    let size = u64::from_be_bytes(manifest[..8].try_into().unwrap());
+    if size > MAX_FILE_SIZE {
+        return Err(invalid_data(&format!(
+            "announced file size {size} exceeds the {MAX_FILE_SIZE}-byte limit"
+        )));
+    }
+    let size = size as usize;
+    let expected_hash = &manifest[8..];
+    println!("Receiving {size} bytes");
+
+    let mut contents = Vec::with_capacity(size);
+    while contents.len() < size {
+        contents.extend_from_slice(&recv_encrypted(&mut stream, &keypair)?);
+    }
+
+    if contents.len() != size {
+        return Err(invalid_data(
+            "received more bytes than the manifest announced",
+        ));
+    }
+    if Sha256::digest(&contents).as_slice() != expected_hash {
+        return Err(invalid_data("hash mismatch; file was corrupted in transit"));
+    }
+
+    fs::write(output, &contents)?;
+    println!("Verified {size} bytes and wrote them to {output}");
+    Ok(())
+}
+
+fn send(addr: &str, input: &str) -> io::Result<()> {
+    let contents = fs::read(input)?;
+    if contents.len() as u64 > MAX_FILE_SIZE {
+        return Err(invalid_data(&format!(
+            "{input} exceeds the {MAX_FILE_SIZE}-byte limit"
+        )));
+    }
+    let mut stream = TcpStream::connect(addr)?;
+
+    let key = NtruPublicKey::from_be_bytes(&read_frame(&mut stream)?)
+        .map_err(|e| invalid_data(&e.to_string()))?;
+
+    let mut manifest = Vec::with_capacity(MANIFEST_LEN);
+    manifest.extend_from_slice(&(contents.len() as u64).to_be_bytes());
+    manifest.extend_from_slice(&Sha256::digest(&contents));
+    send_encrypted(&mut stream, &key, &manifest)?;
+
+    for (i, chunk) in contents.chunks(CHUNK_SIZE).enumerate() {
+        send_encrypted(&mut stream, &key, chunk)?;
+        let sent = (i * CHUNK_SIZE + chunk.len()) as f64;
+        print!("\rSent {:.1}%", 100.0 * sent / contents.len() as f64);
+        io::stdout().flush()?;
+    }
+    println!("\rSent {} bytes from {input}", contents.len());
+    Ok(())
+}
+
+/// Encrypts `bytes` (at most `CHUNK_SIZE` of them) and sends them as one frame.
+fn send_encrypted(stream: &mut TcpStream, key: &NtruPublicKey, bytes: &[u8]) -> io::Result<()> {
+    let enc = key.encrypt_bytes(encode(bytes));
+    write_frame(stream, &enc.to_be_bytes())
+}
+
+/// Receives one frame and decrypts it back into the bytes passed to `send_encrypted`.
+fn recv_encrypted(stream: &mut TcpStream, keypair: &NtruKeyPair) -> io::Result<Vec<u8>> {
+    let enc =
+        ConvPoly::from_be_bytes(&read_frame(stream)?).map_err(|e| invalid_data(&e.to_string()))?;
+    decode(&keypair.private.decrypt_to_bytes(enc))
+}
+
+/// NTRU's byte encoding drops 0 bytes and can't represent bytes above 241, so each byte is split
+/// into two nibbles shifted into 1..=16.
+fn encode(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|b| [(b >> 4) + 1, (b & 0x0f) + 1])
+        .collect()
+}
+
+/// Reverses `encode`.
+fn decode(encoded: &[u8]) -> io::Result<Vec<u8>> {
+    if !encoded.len().is_multiple_of(2) || encoded.iter().any(|&b| !(1..=16).contains(&b)) {
+        return Err(invalid_data("chunk failed to decrypt"));
+    }
+    Ok(encoded
+        .chunks(2)
+        .map(|pair| ((pair[0] - 1) << 4) | (pair[1] - 1))
+        .collect())
+}
+
+fn write_frame(stream: &mut TcpStream, bytes: &[u8]) -> io::Result<()> {
+    stream.write_all(&(bytes.len() as u32).to_be_bytes())?;
+    stream.write_all(bytes)
+}
+
+fn read_frame(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+    let mut len_buf = [0u8; 4];
+    stream.read_exact(&mut len_buf)?;
+    let len = u32::from_be_bytes(len_buf) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(invalid_data(&format!(
+            "frame of {len} bytes exceeds the {MAX_FRAME_LEN}-byte limit"
+        )));
+    }
+    let mut buf = vec![0u8; len];
+    stream.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
@@ -1,3 +1,4 @@
+use crate::ntru_error::NtruError;
 use rand::prelude::*;
 use std::cmp::max;
 use std::fmt;
@@ -169,7 +170,7 @@ impl ConvPoly {
                 let idx = (i + j) % n;
                 let val = self.coeffs[i] * other.coeffs[j];
 
-                if val != 0 && idx > result.deg() {
+                if idx >= result.coeffs.len() {
                     result.coeffs.resize(idx + 1, 0);
                 }
 
@@ -188,7 +189,7 @@ impl ConvPoly {
         divisor: &ConvPoly,
         m: i32,
         n: usize,
-    ) -> Result<(ConvPoly, ConvPoly), String> {
+    ) -> Result<(ConvPoly, ConvPoly), NtruError> {
         assert!(
             !divisor.is_zero(),
             "Division by zero polynomial not permitted"
@@ -199,11 +200,8 @@ impl ConvPoly {
         let mut quotient = ConvPoly::constant(0);
 
         // Check whether the given divisor is valid by attempting to compute the multiplicative inverse of its leading coefficient
-        let inverse_divisor_lc = if let Ok(inverse) = inverse(divisor.lc(), m) {
-            inverse
-        } else {
-            return Err("Invalid divisor polynomial; no multiplicative inverse for its leading coefficient (mod m)".to_string());
-        };
+        let inverse_divisor_lc =
+            inverse(divisor.lc(), m).map_err(|_| NtruError::InvalidDivisor { m })?;
 
         while remainder.deg() >= divisor.deg() && !remainder.is_zero() {
             // Construct the term c * x^d
@@ -225,7 +223,7 @@ impl ConvPoly {
         Ok((quotient, remainder))
     }
 
-    pub fn gcd(a: &ConvPoly, b: &ConvPoly, m: i32, n: usize) -> Result<ConvPoly, String> {
+    pub fn gcd(a: &ConvPoly, b: &ConvPoly, m: i32, n: usize) -> Result<ConvPoly, NtruError> {
         assert!(
             !a.is_zero() || !b.is_zero(),
             "At least one of the polynomials must be non-zero"
@@ -260,7 +258,7 @@ impl ConvPoly {
         b: &ConvPoly,
         m: i32,
         n: usize,
-    ) -> Result<(ConvPoly, ConvPoly, ConvPoly), String> {
+    ) -> Result<(ConvPoly, ConvPoly, ConvPoly), NtruError> {
         assert!(
             !a.is_zero() || !b.is_zero(),
             "At least one of the polynomials must be non-zero"
@@ -299,9 +297,9 @@ impl ConvPoly {
 
     /// Computes the inverse of this polynomial within the ring (Z/mZ)\[x\]/(x^n - 1) using
     /// the Extended Euclidean Algorithm. Returns an error if the polynomial is not invertible.
-    pub fn inverse(&self, m: i32, n: usize) -> Result<ConvPoly, String> {
+    pub fn inverse(&self, m: i32, n: usize) -> Result<ConvPoly, NtruError> {
         if self.is_zero() {
-            return Err("The inverse of the zero polynomial does not exist.".to_string());
+            return Err(NtruError::NotInvertible { m, n });
         }
 
         // Create the modulus polynomial x^n - 1
@@ -316,7 +314,7 @@ impl ConvPoly {
         let (gcd, s, _) = ConvPoly::extended_gcd(&self, &mod_poly, m, n + 1)?;
 
         if gcd != ConvPoly::constant(1) {
-            return Err("The polynomial is not invertible in the given ring.".to_string());
+            return Err(NtruError::NotInvertible { m, n });
         }
 
         Ok(s)
@@ -333,15 +331,20 @@ impl ConvPoly {
     }
 
     /// Deserializes a byte vector into a convolution polynomial. The byte vector is assumed to be
-    /// in big-endian format with each coefficient represented by 4 bytes.
-    pub fn from_be_bytes(buf: &Vec<u8>) -> ConvPoly {
-        let mut coeffs = Vec::new();
+    /// in big-endian format with each coefficient represented by 4 bytes. Returns an error if the
+    /// buffer length is not a multiple of 4.
+    pub fn from_be_bytes(buf: &[u8]) -> Result<ConvPoly, NtruError> {
+        if !buf.len().is_multiple_of(size_of::<i32>()) {
+            return Err(NtruError::InvalidLength { len: buf.len() });
+        }
+
+        let mut coeffs = Vec::with_capacity(buf.len() / size_of::<i32>());
         for i in (0..buf.len()).step_by(size_of::<i32>()) {
             let coeff = i32::from_be_bytes([buf[i], buf[i + 1], buf[i + 2], buf[i + 3]]);
             coeffs.push(coeff);
         }
 
-        ConvPoly { coeffs }
+        Ok(ConvPoly { coeffs })
     }
 }
 
@@ -390,20 +393,18 @@ pub fn extended_gcd(a: i32, b: i32) -> (i32, i32, i32) {
 
 /// Returns the multiplicative inverse of `a` within the unit group (Z/mZ)*. Returns an error if no
 /// such inverse exists (i.e. if `a` is not relatively prime to `m`, and therefore not a member of the group).
-pub fn inverse(a: i32, m: i32) -> Result<i32, String> {
+pub fn inverse(a: i32, m: i32) -> Result<i32, NtruError> {
     assert!(m > 0, "Modulus `m` must be a positive integer");
 
     if a == 0 {
-        return Err("The multiplicative inverse of 0 does not exist.".to_string());
+        return Err(NtruError::NoInverse { a, m });
     }
 
     let (gcd, x, _) = extended_gcd(a.rem_euclid(m), m);
 
+    // `a` only has a multiplicative inverse (mod m) if it is relatively prime to m
     if gcd != 1 {
-        return Err(
-            "`a` only has a multiplicative inverse (mod m) if it is relatively prime to m."
-                .to_string(),
-        );
+        return Err(NtruError::NoInverse { a, m });
     }
 
     Ok(x.rem_euclid(m))
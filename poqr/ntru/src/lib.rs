@@ -1,7 +1,11 @@
 pub mod convolution_polynomial;
+pub mod ntru_error;
 pub mod ntru_key;
 pub mod ntru_util;
 pub mod params;
+#[cfg(feature = "proptest")]
+pub mod strategies;
 // Exported from ntru crate
 pub use convolution_polynomial::ConvPoly;
+pub use ntru_error::NtruError;
 pub use ntru_key::{NtruKeyPair, NtruPrivateKey, NtruPublicKey};
@@ -0,0 +1,27 @@
+use thiserror::Error;
+
+/// Errors produced by the NTRU crate
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum NtruError {
+    /// `a` has no multiplicative inverse in the unit group (Z/mZ)*
+    #[error("{a} has no multiplicative inverse (mod {m})")]
+    NoInverse { a: i32, m: i32 },
+    /// The leading coefficient of a divisor polynomial is not a unit in the ring Z/mZ
+    #[error("invalid divisor polynomial; no multiplicative inverse for its leading coefficient (mod {m})")]
+    InvalidDivisor { m: i32 },
+    /// The polynomial has no inverse in the ring (Z/mZ)\[x\]/(x^n - 1)
+    #[error("the polynomial is not invertible in (Z/{m}Z)[x]/(x^{n} - 1)")]
+    NotInvertible { m: i32, n: usize },
+    /// A byte buffer doesn't hold a whole number of 4-byte coefficients
+    #[error("buffer of {len} bytes is not a whole number of 4-byte coefficients")]
+    InvalidLength { len: usize },
+    /// A plaintext is too long to fit in a single NTRU message
+    #[error("plaintext of {len} bytes exceeds the {max}-byte limit of one NTRU message")]
+    PlaintextTooLong { len: usize, max: usize },
+    /// A plaintext byte has no 5-digit balanced ternary encoding
+    #[error("plaintext byte {0} can't be encoded; bytes must be at most 241")]
+    UnencodableByte(u8),
+    /// A serialized private key is truncated, has trailing bytes, or holds a non-ternary polynomial
+    #[error("malformed private key: {0}")]
+    MalformedPrivateKey(&'static str),
+}
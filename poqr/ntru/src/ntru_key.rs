@@ -1,5 +1,6 @@
 use crate::convolution_polynomial::{ternary_polynomial, ConvPoly};
-use crate::ntru_util::{deserialize, serialize};
+use crate::ntru_error::NtruError;
+use crate::ntru_util::{deserialize, serialize, try_serialize};
 use crate::params::*;
 
 #[derive(Clone)]
@@ -21,9 +22,18 @@ impl NtruKeyPair {
             private: k_priv,
         }
     }
+
+    /// Rebuilds a key pair from its private key, e.g. one loaded from disk
+    pub fn from_private(k_priv: NtruPrivateKey) -> NtruKeyPair {
+        let k_pub = NtruPublicKey::new(&k_priv);
+        NtruKeyPair {
+            public: k_pub,
+            private: k_priv,
+        }
+    }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 /// A public key used in the NTRU encryption scheme
 pub struct NtruPublicKey {
     h: ConvPoly,
@@ -34,8 +44,8 @@ impl NtruPublicKey {
     fn new(k_priv: &NtruPrivateKey) -> NtruPublicKey {
         // Generate f inverse over Q
         let f_inv = &k_priv.f_q;
-        // Public key generated as f inverse Q * g
-        let h = f_inv.mul(&k_priv.g, N);
+        // Public key generated as f inverse Q * g, reduced mod Q to match from_be_bytes
+        let h = f_inv.mul(&k_priv.g, N).modulo(Q);
         NtruPublicKey { h }
     }
 
@@ -56,6 +66,12 @@ impl NtruPublicKey {
         self.encrypt_poly(serialize(msg))
     }
 
+    /// Like `encrypt_bytes`, but returns an error instead of panicking when the message doesn't
+    /// fit in a single NTRU message
+    pub fn try_encrypt_bytes(&self, msg: Vec<u8>) -> Result<ConvPoly, NtruError> {
+        Ok(self.encrypt_poly(try_serialize(msg)?))
+    }
+
     /// Serializes the public key into a byte vector
     pub fn to_be_bytes(&self) -> Vec<u8> {
         let mut buf = Vec::with_capacity(self.h.coeffs.len() * size_of::<i32>());
@@ -66,16 +82,10 @@ impl NtruPublicKey {
     }
 
     /// Deserializes a byte vector into an NTRU public key
-    pub fn from_be_bytes(buf: &[u8]) -> NtruPublicKey {
-        let mut coeffs = Vec::with_capacity(buf.len() / size_of::<i32>());
-        for chunk in buf.chunks(size_of::<i32>()) {
-            let mut bytes = [0; size_of::<i32>()];
-            bytes.copy_from_slice(chunk);
-            coeffs.push(i32::from_be_bytes(bytes));
-        }
-        NtruPublicKey {
-            h: ConvPoly { coeffs },
-        }
+    pub fn from_be_bytes(buf: &[u8]) -> Result<NtruPublicKey, NtruError> {
+        // Reduce the coefficients so that a malformed key can't overflow during encryption
+        let h = ConvPoly::from_be_bytes(buf)?.modulo(Q);
+        Ok(NtruPublicKey { h })
     }
 }
 
@@ -109,6 +119,62 @@ impl NtruPrivateKey {
         }
     }
 
+    /// Serializes the private key into a byte vector. Only f(x) and g(x) are stored, each as a
+    /// 4-byte big-endian length followed by its coefficients; the inverses are recomputed on load.
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for poly in [&self.f, &self.g] {
+            let bytes = poly.to_be_bytes();
+            buf.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            buf.extend_from_slice(&bytes);
+        }
+        buf
+    }
+
+    /// Deserializes a byte vector produced by `to_be_bytes` into an NTRU private key. Returns an
+    /// error if the buffer is malformed, f(x) or g(x) isn't a ternary polynomial of at most N
+    /// coefficients, or f(x) is not invertible modulo P and Q.
+    pub fn from_be_bytes(buf: &[u8]) -> Result<NtruPrivateKey, NtruError> {
+        let (f, rest) = NtruPrivateKey::read_poly(buf)?;
+        let (g, rest) = NtruPrivateKey::read_poly(rest)?;
+        if !rest.is_empty() {
+            return Err(NtruError::MalformedPrivateKey("trailing bytes after g(x)"));
+        }
+
+        let f_p = f.inverse(P, N)?;
+        let f_q = f.inverse(Q, N)?;
+        Ok(NtruPrivateKey { f, f_p, f_q, g })
+    }
+
+    /// Reads one length-prefixed ternary polynomial from the front of `buf`, returning it and the
+    /// remaining bytes
+    fn read_poly(buf: &[u8]) -> Result<(ConvPoly, &[u8]), NtruError> {
+        let truncated = NtruError::MalformedPrivateKey("truncated polynomial");
+        if buf.len() < size_of::<u32>() {
+            return Err(truncated);
+        }
+        let (len_bytes, rest) = buf.split_at(size_of::<u32>());
+        let len = u32::from_be_bytes(len_bytes.try_into().unwrap()) as usize;
+        if rest.len() < len {
+            return Err(truncated);
+        }
+        let (poly_bytes, rest) = rest.split_at(len);
+        let poly = ConvPoly::from_be_bytes(poly_bytes).map_err(|_| truncated)?;
+
+        // Larger coefficients would overflow when the public key is recomputed from f(x) and g(x)
+        if poly.coeffs.len() > N {
+            return Err(NtruError::MalformedPrivateKey(
+                "polynomial has more than N coefficients",
+            ));
+        }
+        if poly.coeffs.iter().any(|c| !(-1..=1).contains(c)) {
+            return Err(NtruError::MalformedPrivateKey(
+                "polynomial coefficients must be -1, 0 or 1",
+            ));
+        }
+        Ok((poly, rest))
+    }
+
     /// Decrypts a polynomial-encoded message using the NTRU encryption scheme into a byte vector
     /// ONLY FUNCTIONAL ON SINGLE LAYER ENCRYPTION ; MULTIPLE LAYERS WILL BREAK!
     pub fn decrypt_to_bytes(&self, enc_msg: ConvPoly) -> Vec<u8> {
@@ -118,8 +184,8 @@ impl NtruPrivateKey {
     /// Decrypts a polynomial-encoded message using the NTRU encryption scheme into another polynomial
     /// ONLY FUNCTIONAL ON MULTI-LAYERED ENCRYPTION : FINAL LAYER WILL BREAK!
     pub fn decrypt_to_poly(&self, enc_msg: ConvPoly) -> ConvPoly {
-        // a(x) ≡ e(x) * f(x) (mod q)
-        let a = enc_msg.mul(&self.f, N).center_lift(Q);
+        // a(x) ≡ e(x) * f(x) (mod q); e(x) is reduced first so malformed input can't overflow
+        let a = enc_msg.modulo(Q).mul(&self.f, N).center_lift(Q);
         // m(x) ≡ a(x) * Fp(x) (mod p)
         let msg_poly = a.mul(&self.f_p, N).modulo(P);
         msg_poly
@@ -1,6 +1,10 @@
 use crate::convolution_polynomial::*;
+use crate::ntru_error::NtruError;
 use crate::params::*;
 
+/// Largest byte the 5-digit balanced ternary encoding can represent
+const MAX_ENCODABLE_BYTE: u8 = 241;
+
 /// Takes in a plain message encoded in ASCII and returns a convolution polynomial with coefficients representing that message
 pub fn serialize(plain_msg: Vec<u8>) -> ConvPoly {
     assert!(
@@ -17,6 +21,21 @@ pub fn serialize(plain_msg: Vec<u8>) -> ConvPoly {
     ConvPoly { coeffs: digit_vec }
 }
 
+/// Like `serialize`, but returns an error instead of panicking when the message is longer than
+/// N / 5 bytes or contains a byte above 241
+pub fn try_serialize(plain_msg: Vec<u8>) -> Result<ConvPoly, NtruError> {
+    if plain_msg.len() * 5 > N {
+        return Err(NtruError::PlaintextTooLong {
+            len: plain_msg.len(),
+            max: N / 5,
+        });
+    }
+    if let Some(&c) = plain_msg.iter().find(|&&c| c > MAX_ENCODABLE_BYTE) {
+        return Err(NtruError::UnencodableByte(c));
+    }
+    Ok(serialize(plain_msg))
+}
+
 /// Converts a 32 bit integer to a balanced ternary representation in the form of a 5-integer array
 /// Max value is 242
 fn ternary(mut c: i32) -> [i32; 5] {
@@ -0,0 +1,33 @@
+use crate::convolution_polynomial::ConvPoly;
+use crate::ntru_key::{NtruKeyPair, NtruPublicKey};
+use crate::params::*;
+use proptest::prelude::*;
+
+/// Generates convolution polynomials with up to `max_len` arbitrary (possibly out of range) coefficients
+pub fn conv_poly(max_len: usize) -> impl Strategy<Value = ConvPoly> {
+    prop::collection::vec(any::<i32>(), 0..=max_len).prop_map(|coeffs| ConvPoly { coeffs })
+}
+
+/// Generates ternary polynomials of degree less than `n`, i.e. elements of the ring Z\[x\]/(x^n - 1)
+/// with coefficients in {-1, 0, 1}
+pub fn ternary_poly(n: usize) -> impl Strategy<Value = ConvPoly> {
+    prop::collection::vec(-1..=1i32, 1..=n).prop_map(|coeffs| ConvPoly { coeffs }.trim())
+}
+
+/// Generates polynomials of degree less than N with coefficients reduced into \[0, m)
+pub fn reduced_poly(m: i32) -> impl Strategy<Value = ConvPoly> {
+    prop::collection::vec(0..m, 1..=N).prop_map(|coeffs| ConvPoly { coeffs }.trim())
+}
+
+/// Generates plaintexts that fit in a single NTRU message. Each byte takes 5 balanced ternary
+/// digits, so at most N / 5 bytes fit, and only 1..=241 round-trip: 0 encodes as padding and
+/// 242 and up need a sixth digit.
+pub fn plaintext() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(1..=241u8, 0..=N / 5)
+}
+
+/// Generates public keys the way production code does, through `NtruKeyPair::new`. Key generation
+/// is slow and takes its randomness from the thread RNG, so keep case counts low.
+pub fn public_key() -> impl Strategy<Value = NtruPublicKey> {
+    Just(()).prop_map(|_| NtruKeyPair::new().public)
+}
@@ -3,6 +3,7 @@ mod tests {
     use ntru::convolution_polynomial::{
         center_lift, extended_gcd, gcd, inverse, ternary_polynomial, ConvPoly,
     };
+    use ntru::NtruError;
     use rand::Rng;
 
     mod ternary_polynomial_tests {
@@ -909,6 +910,13 @@ mod tests {
 
             // Should fail assert and panic when a is 0
             assert!(inverse(0, 7).is_err(), "Inverse should not exist for 0");
+
+            // Errors should report which element and modulus failed
+            assert_eq!(
+                inverse(6, 9),
+                Err(NtruError::NoInverse { a: 6, m: 9 }),
+                "Wrong error for non-coprime"
+            );
         }
 
         #[test]
@@ -1,7 +1,12 @@
 #[cfg(test)]
 mod ntru_key_tests {
-    use ntru::{convolution_polynomial::ternary_polynomial, ntru_key::NtruKeyPair, ConvPoly};
-    use rand::Rng; 
+    use ntru::{
+        convolution_polynomial::ternary_polynomial,
+        ntru_key::{NtruKeyPair, NtruPrivateKey},
+        params::{D, N},
+        ConvPoly, NtruError,
+    };
+    use rand::Rng;
 
     #[test]
     fn test_bytecode() {
@@ -11,7 +16,101 @@ mod ntru_key_tests {
         let poly = ternary_polynomial(n, num_ones, num_neg_ones);
 
         let enc_poly = poly.to_be_bytes();
-        assert!(ConvPoly::from_be_bytes(&enc_poly) == poly, "Failed encoding polynomial")
+        assert!(
+            ConvPoly::from_be_bytes(&enc_poly).expect("Decoding should succeed") == poly,
+            "Failed encoding polynomial"
+        );
+
+        // Buffers that aren't a whole number of coefficients are rejected
+        assert!(
+            ConvPoly::from_be_bytes(&[0, 0, 1]).is_err(),
+            "Truncated buffer should fail to decode"
+        );
+    }
+
+    #[test]
+    fn test_private_key_bytecode() {
+        let keypair = NtruKeyPair::new();
+        let bytes = keypair.private.to_be_bytes();
+        let private = NtruPrivateKey::from_be_bytes(&bytes).expect("Decoding should succeed");
+        let reloaded = NtruKeyPair::from_private(private);
+
+        // The reloaded key pair should have the same public key and decrypt the original's messages
+        assert_eq!(
+            reloaded.public.to_be_bytes(),
+            keypair.public.to_be_bytes(),
+            "Public key changed after reloading"
+        );
+        let msg = "Hello World".as_bytes().to_vec();
+        let enc_msg = keypair.public.encrypt_bytes(msg.clone());
+        assert_eq!(
+            reloaded.private.decrypt_to_bytes(enc_msg),
+            msg,
+            "Reloaded key failed to decrypt"
+        );
+
+        // Truncated keys and trailing bytes are rejected
+        assert!(
+            matches!(
+                NtruPrivateKey::from_be_bytes(&bytes[..bytes.len() - 1]),
+                Err(NtruError::MalformedPrivateKey(_))
+            ),
+            "Truncated key should fail to decode"
+        );
+        let mut extended = bytes.clone();
+        extended.extend_from_slice(&[0; 4]);
+        assert!(
+            matches!(
+                NtruPrivateKey::from_be_bytes(&extended),
+                Err(NtruError::MalformedPrivateKey(_))
+            ),
+            "Key with trailing bytes should fail to decode"
+        );
+    }
+
+    #[test]
+    fn test_private_key_rejects_non_ternary() {
+        let keypair = NtruKeyPair::new();
+        let f = ternary_polynomial(N, D + 1, D);
+
+        // A huge coefficient in g(x) would overflow when the public key is recomputed
+        let mut g = ternary_polynomial(N, D, D);
+        g.coeffs[0] = 1 << 30;
+        let bytes = private_key_bytes(&f, &g);
+        assert!(
+            matches!(
+                NtruPrivateKey::from_be_bytes(&bytes),
+                Err(NtruError::MalformedPrivateKey(_))
+            ),
+            "Non-ternary g(x) should fail to decode"
+        );
+
+        // So would a polynomial longer than N
+        let mut g = ternary_polynomial(N, D, D);
+        g.coeffs.resize(N + 1, 0);
+        g.coeffs[N] = 1;
+        let bytes = private_key_bytes(&f, &g);
+        assert!(
+            matches!(
+                NtruPrivateKey::from_be_bytes(&bytes),
+                Err(NtruError::MalformedPrivateKey(_))
+            ),
+            "g(x) with more than N coefficients should fail to decode"
+        );
+
+        // The real key still decodes
+        assert!(NtruPrivateKey::from_be_bytes(&keypair.private.to_be_bytes()).is_ok());
+    }
+
+    /// Serializes f(x) and g(x) in the private key format
+    fn private_key_bytes(f: &ConvPoly, g: &ConvPoly) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for poly in [f, g] {
+            let bytes = poly.to_be_bytes();
+            buf.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            buf.extend_from_slice(&bytes);
+        }
+        buf
     }
 
     #[test]
@@ -39,13 +138,12 @@ mod ntru_key_tests {
         println!("Message 3: {:?}", msg);
         let enc_msg = keypair.public.encrypt_bytes(msg.clone());
         let enc_msg_bytes = enc_msg.to_be_bytes();
-        let enc_msg_debyted = ConvPoly::from_be_bytes(&enc_msg_bytes);
+        let enc_msg_debyted =
+            ConvPoly::from_be_bytes(&enc_msg_bytes).expect("Decoding should succeed");
         let dec_msg = keypair.private.decrypt_to_bytes(enc_msg_debyted);
         println!("dec_msg as string: {}", String::from_utf8_lossy(&dec_msg));
         assert_eq!(msg, dec_msg, "debyting message failed");
 
-
-
         // // Test random messages with new key pairs
         // let num_tests = 100;
         // let mut rng = rand::thread_rng();
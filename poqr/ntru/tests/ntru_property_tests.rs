@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod ntru_property_tests {
+    use ntru::ntru_util::{deserialize, serialize};
+    use ntru::params::{N, Q};
+    use ntru::strategies::{conv_poly, plaintext, public_key, reduced_poly, ternary_poly};
+    use ntru::{ConvPoly, NtruKeyPair, NtruPublicKey};
+    use proptest::prelude::*;
+    use std::sync::OnceLock;
+
+    /// Key generation is slow, so the encryption properties share one key pair
+    fn shared_keypair() -> &'static NtruKeyPair {
+        static KEYPAIR: OnceLock<NtruKeyPair> = OnceLock::new();
+        KEYPAIR.get_or_init(NtruKeyPair::new)
+    }
+
+    proptest! {
+        #[test]
+        fn test_conv_poly_bytes_round_trip(poly in conv_poly(64)) {
+            let bytes = poly.to_be_bytes();
+            prop_assert_eq!(ConvPoly::from_be_bytes(&bytes), Ok(poly));
+        }
+
+        #[test]
+        fn test_serialize_round_trip(msg in plaintext()) {
+            prop_assert_eq!(deserialize(serialize(msg.clone())), msg);
+        }
+
+        #[test]
+        fn test_public_key_bytes_round_trip(poly in reduced_poly(Q)) {
+            let bytes = poly.to_be_bytes();
+            let key = NtruPublicKey::from_be_bytes(&bytes).expect("Decoding should succeed");
+            prop_assert_eq!(key.to_be_bytes(), bytes);
+        }
+
+        #[test]
+        fn test_public_key_bytes_equivalent_mod_q(poly in conv_poly(N)) {
+            // Any coefficients decode to the key they are congruent to mod Q
+            let key = NtruPublicKey::from_be_bytes(&poly.to_be_bytes()).expect("Decoding should succeed");
+            prop_assert_eq!(key.to_be_bytes(), poly.modulo(Q).to_be_bytes());
+        }
+
+        #[test]
+        fn test_mul_commutes(a in ternary_poly(N), b in ternary_poly(N)) {
+            prop_assert_eq!(a.mul(&b, N), b.mul(&a, N));
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn test_generated_public_key_bytes_round_trip(public_key in public_key()) {
+            let bytes = public_key.to_be_bytes();
+            let key = NtruPublicKey::from_be_bytes(&bytes).expect("Decoding should succeed");
+            prop_assert_eq!(key.to_be_bytes(), bytes);
+        }
+
+        #[test]
+        fn test_encrypt_decrypt_round_trip(msg in plaintext()) {
+            let keypair = shared_keypair();
+            let enc_msg = keypair.public.encrypt_bytes(msg.clone());
+            let enc_msg = ConvPoly::from_be_bytes(&enc_msg.to_be_bytes()).expect("Decoding should succeed");
+            prop_assert_eq!(keypair.private.decrypt_to_bytes(enc_msg), msg);
+        }
+    }
+}
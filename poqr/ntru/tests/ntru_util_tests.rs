@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod ntru_util_tests {
-    use ntru::ntru_util::{deserialize, serialize};
+    use ntru::ntru_util::{deserialize, serialize, try_serialize};
+    use ntru::NtruError;
 
     #[test]
     fn test_serialize() {
@@ -29,4 +30,21 @@ mod ntru_util_tests {
         assert_eq!(msg.as_bytes().to_vec(), deser);
         println!("characters in message: {}", msg.len());
     }
+
+    #[test]
+    fn test_try_serialize() {
+        let msg = b"hello".to_vec();
+        assert_eq!(try_serialize(msg.clone()), Ok(serialize(msg)));
+
+        assert_eq!(
+            try_serialize(vec![1; 133]),
+            Err(NtruError::PlaintextTooLong { len: 133, max: 132 }),
+            "Message longer than N / 5 bytes should be rejected"
+        );
+        assert_eq!(
+            try_serialize(vec![1, 242, 3]),
+            Err(NtruError::UnencodableByte(242)),
+            "Bytes above 241 should be rejected"
+        );
+    }
 }
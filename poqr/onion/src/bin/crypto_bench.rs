@@ -0,0 +1,136 @@
+//! Measures how many cells per second each onion-layer crypto scheme can wrap and unwrap.
+//!
+//! Usage: crypto_bench [cells per run]
+//!
+//! Every hop of a circuit adds one layer on the way out and removes it on the way back, so a run
+//! encodes and decodes each cell once per hop. The "packet" scheme sends a DATA relay message
+//! through OnionPacket::to_be_bytes/from_be_bytes, i.e. the NTRU identity layer a channel applies.
+//! The current wire format can't nest layers (NTRU plaintexts are capped at N / 5 bytes in
+//! 1..=241, which an RSA-1024 ciphertext doesn't fit), so packets carry no RSA onion skin and each
+//! hop encodes a fresh packet. The "rsa-1hop" scheme measures the PKCS#1 v1.5 onion skin on its
+//! own: each hop encrypts the plain cell under its key rather than nesting the layers.
+use ntru::NtruKeyPair;
+use onion::{DataPayload, Message, OnionHeader, OnionPacket, RelayPayload};
+use rand::Rng;
+use rsa_ext::{PaddingScheme, PublicKey, RsaPrivateKey, RsaPublicKey};
+use std::time::{Duration, Instant};
+
+/// Number of hops in the simulated circuit
+const LAYERS: usize = 3;
+/// Cell sizes to measure, in bytes. 117 is the largest plaintext RSA-1024 with PKCS#1 v1.5 accepts.
+const CELL_SIZES: [usize; 3] = [16, 64, 117];
+/// Cells per run when no count is given on the command line
+const DEFAULT_CELLS: usize = 100;
+
+fn main() {
+    let cells = match std::env::args().nth(1) {
+        Some(arg) => arg.parse().unwrap_or_else(|_| {
+            eprintln!("usage: crypto_bench [cells per run]");
+            std::process::exit(1);
+        }),
+        None => DEFAULT_CELLS,
+    };
+
+    println!("Generating keys for {LAYERS} hops...");
+    let mut rng = rand::thread_rng();
+    let rsa_keys: Vec<RsaPrivateKey> = (0..LAYERS)
+        .map(|_| RsaPrivateKey::new(&mut rng, 1024).unwrap())
+        .collect();
+    let ntru_keys: Vec<NtruKeyPair> = (0..LAYERS).map(|_| NtruKeyPair::new()).collect();
+
+    println!("rsa-1hop: one RSA layer per hop, each over the plain cell (layers are not nested)");
+    println!("packet:   OnionPacket encode/decode with the NTRU identity layer, once per hop");
+    println!(
+        "{:<10} {:>10} {:>12} {:>14}",
+        "scheme", "cell size", "cells", "cells/sec"
+    );
+    for cell_size in CELL_SIZES {
+        let batch: Vec<Vec<u8>> = (0..cells).map(|_| random_cell(cell_size)).collect();
+
+        let elapsed = bench_rsa(&rsa_keys, &batch);
+        report("rsa-1hop", cell_size, cells, elapsed);
+
+        let elapsed = bench_packet(&ntru_keys, &batch);
+        report("packet", cell_size, cells, elapsed);
+    }
+    println!("symmetric layering is not implemented yet; no numbers to report");
+}
+
+/// Generates a random cell. Bytes are kept in 1..=241 so that NTRU's byte encoding round-trips them.
+fn random_cell(cell_size: usize) -> Vec<u8> {
+    let mut rng = rand::thread_rng();
+    (0..cell_size).map(|_| rng.gen_range(1..=241)).collect()
+}
+
+fn bench_rsa(keys: &[RsaPrivateKey], batch: &[Vec<u8>]) -> Duration {
+    let public_keys: Vec<RsaPublicKey> = keys.iter().map(RsaPublicKey::from).collect();
+    let mut rng = rand::thread_rng();
+    let mut decoded = Vec::with_capacity(batch.len() * keys.len());
+
+    let start = Instant::now();
+    for cell in batch {
+        for (public_key, private_key) in public_keys.iter().zip(keys) {
+            let padding = PaddingScheme::new_pkcs1v15_encrypt();
+            let enc = public_key.encrypt(&mut rng, padding, cell).unwrap();
+            let padding = PaddingScheme::new_pkcs1v15_encrypt();
+            decoded.push(private_key.decrypt(padding, &enc).unwrap());
+        }
+    }
+    let elapsed = start.elapsed();
+
+    for (dec, cell) in decoded
+        .iter()
+        .zip(batch.iter().flat_map(|cell| [cell; LAYERS]))
+    {
+        assert_eq!(dec, cell, "RSA layer failed to round-trip");
+    }
+    elapsed
+}
+
+fn bench_packet(keys: &[NtruKeyPair], batch: &[Vec<u8>]) -> Duration {
+    let mut decoded = Vec::with_capacity(batch.len() * keys.len());
+    // The encode/decode calls take keys by value; clone them up front so copying the private
+    // key's polynomials isn't part of the timing
+    let hop_keys: Vec<Vec<_>> = batch
+        .iter()
+        .map(|_| {
+            keys.iter()
+                .map(|key| (key.public.clone(), key.private.clone()))
+                .collect()
+        })
+        .collect();
+
+    let start = Instant::now();
+    for ((circ_id, cell), hop_keys) in batch.iter().enumerate().zip(hop_keys) {
+        for (public_key, private_key) in hop_keys {
+            let packet = OnionPacket {
+                header: OnionHeader {
+                    circ_id: circ_id as u32,
+                },
+                msg: Message::Relay(RelayPayload::Data(DataPayload::from_be_bytes(cell))),
+            };
+            let bytes = packet.to_be_bytes(public_key, Vec::new()).unwrap();
+            let packet = OnionPacket::from_be_bytes(&bytes, private_key, Vec::new());
+            decoded.push(packet.unwrap());
+        }
+    }
+    let elapsed = start.elapsed();
+
+    for (packet, cell) in decoded
+        .iter()
+        .zip(batch.iter().flat_map(|cell| [cell; LAYERS]))
+    {
+        match &packet.msg {
+            Message::Relay(RelayPayload::Data(data)) => {
+                assert_eq!(&data.to_be_bytes(), cell, "Packet failed to round-trip")
+            }
+            _ => panic!("Packet decoded to the wrong message type"),
+        }
+    }
+    elapsed
+}
+
+fn report(scheme: &str, cell_size: usize, cells: usize, elapsed: Duration) {
+    let rate = cells as f64 / elapsed.as_secs_f64();
+    println!("{scheme:<10} {cell_size:>10} {cells:>12} {rate:>14.1}");
+}
@@ -1,4 +1,4 @@
-use crate::{Message, OnionHeader, OnionPacket};
+use crate::{Message, OnionError, OnionHeader, OnionPacket, MAX_MESSAGE_LEN};
 use ntru::ntru_key::{NtruPrivateKey, NtruPublicKey};
 use rsa_ext::{RsaPrivateKey, RsaPublicKey};
 use std::io::{Read, Write};
@@ -27,46 +27,60 @@ impl Channel {
         let mut channel = self.clone();
 
         std::thread::spawn(move || loop {
-            let packet = channel.recv();
+            let packet = match channel.recv() {
+                Ok(packet) => packet,
+                Err(e) => {
+                    eprintln!("Closing channel listener: {e}");
+                    break;
+                }
+            };
             // Send the packet to the main listener thread
             channel.packet_sender.send(packet).unwrap();
         });
     }
 
-    pub fn send(&mut self, id: u32, msg: Message) {
+    pub fn send(&mut self, id: u32, msg: Message) -> Result<(), OnionError> {
         let packet = Channel::build_packet(id, msg);
         let bytes = packet.to_be_bytes(
             (*self.forward_id_key).clone(),
             (*self.forward_onion_keys.lock().unwrap()).clone(),
-        );
+        )?;
 
         let mut connection = self.connection.lock().unwrap();
-        connection.write(&bytes).unwrap();
+        connection.write_all(&bytes)?;
+        Ok(())
     }
 
-    pub fn recv(&mut self) -> OnionPacket {
+    pub fn recv(&mut self) -> Result<OnionPacket, OnionError> {
         let mut connection = self.connection.lock().unwrap();
 
         // Read the circuit ID
         let mut circ_id_buf = [0u8; 4];
-        connection.read_exact(&mut circ_id_buf).unwrap();
+        connection.read_exact(&mut circ_id_buf)?;
         let circ_id: u32 = u32::from_be_bytes(circ_id_buf);
 
         // Read the message length
         let mut msg_len_buf = [0u8; 4];
-        connection.read_exact(&mut msg_len_buf).unwrap();
+        connection.read_exact(&mut msg_len_buf)?;
         let msg_len = u32::from_be_bytes(msg_len_buf) as usize;
+        // Check the length before allocating so a peer can't make us reserve gigabytes
+        if msg_len > MAX_MESSAGE_LEN {
+            return Err(OnionError::MessageTooLong {
+                len: msg_len,
+                max: MAX_MESSAGE_LEN,
+            });
+        }
 
         // Read the message
         let mut msg_buf = vec![0u8; msg_len];
-        connection.read_exact(&mut msg_buf).unwrap();
+        connection.read_exact(&mut msg_buf)?;
         let msg: Message = Message::from_be_bytes(
             msg_buf,
             (*self.backward_id_key).clone(),
             (*self.backward_onion_keys).clone(),
-        );
+        )?;
 
-        Channel::build_packet(circ_id, msg)
+        Ok(Channel::build_packet(circ_id, msg))
     }
 
     fn build_packet(id: u32, msg: Message) -> OnionPacket {
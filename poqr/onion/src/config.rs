@@ -0,0 +1,73 @@
+use crate::OnionError;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Number of relays in a circuit when the config doesn't say otherwise
+pub const DEFAULT_CIRCUIT_LENGTH: usize = 3;
+
+/// The configuration of an onion node (relay or host), loaded from a config file.
+///
+/// The file holds one directive per line; blank lines and lines starting with "#" are ignored:
+///
+/// ```text
+/// port 9001
+/// id-key keys/relay.ntru
+/// circuit-length 3
+/// ```
+///
+/// Circuit length only matters to hosts, since relays don't build circuits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OnionConfig {
+    /// The port on which the node listens for incoming connections
+    pub port: u16,
+    /// Path to the node's NTRU identity key file; a new key is saved there if it doesn't exist
+    pub id_key_path: Option<PathBuf>,
+    /// Number of relays in circuits built by this node
+    pub circuit_length: usize,
+}
+
+impl OnionConfig {
+    /// Load and parse the config file at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<OnionConfig, OnionError> {
+        OnionConfig::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parse a config from the contents of a config file.
+    pub fn parse(text: &str) -> Result<OnionConfig, OnionError> {
+        let mut port = None;
+        let mut id_key_path = None;
+        let mut circuit_length = DEFAULT_CIRCUIT_LENGTH;
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            // Line numbers in errors are 1-based to match editors
+            let invalid = |reason: &str| OnionError::InvalidConfigLine {
+                line: i + 1,
+                reason: reason.to_string(),
+            };
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+
+            match tokens.as_slice() {
+                ["port", p] => port = Some(p.parse().map_err(|_| invalid("invalid port"))?),
+                ["id-key", path] => id_key_path = Some(PathBuf::from(path)),
+                ["circuit-length", len] => {
+                    circuit_length = match len.parse() {
+                        Ok(len) if len > 0 => len,
+                        _ => return Err(invalid("circuit length must be a positive integer")),
+                    }
+                }
+                _ => return Err(invalid("unknown directive")),
+            }
+        }
+
+        Ok(OnionConfig {
+            port: port.ok_or(OnionError::MissingConfigField("port"))?,
+            id_key_path,
+            circuit_length,
+        })
+    }
+}
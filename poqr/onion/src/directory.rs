@@ -1,9 +1,13 @@
 use crate::nodes::Relay;
+use crate::{OnionConfig, OnionError};
 use ntru::ntru_key::NtruPublicKey;
-use rand::Rng;
+use ntru::NtruKeyPair;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
 use std::collections::{HashMap, HashSet};
 use std::net::UdpSocket;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 
 pub type RelayId = u32;
 
@@ -22,14 +26,26 @@ pub struct Directory {
     used_ports: HashSet<u16>,
     /// Next relay ID to assign
     next_relay_id: u32,
+    /// Random number generator used for relay selection
+    rng: Mutex<StdRng>,
 }
 
 impl Directory {
     pub fn new() -> Directory {
+        Directory::with_rng(StdRng::from_entropy())
+    }
+
+    /// Create a directory whose relay selection is reproducible from the given seed.
+    pub fn with_seed(seed: u64) -> Directory {
+        Directory::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(rng: StdRng) -> Directory {
         Directory {
             relays: HashMap::new(),
             used_ports: HashSet::new(),
             next_relay_id: 0,
+            rng: Mutex::new(rng),
         }
     }
 
@@ -59,26 +75,55 @@ impl Directory {
 
     /// Generate a new relay and return its ID.
     pub fn generate_relay(directory: Arc<RwLock<Directory>>) -> RelayId {
+        Directory::generate_relay_with_key(directory, NtruKeyPair::new())
+    }
+
+    /// Generate a new relay that uses an existing identity key and return its ID.
+    pub fn generate_relay_with_key(
+        directory: Arc<RwLock<Directory>>,
+        id_key: NtruKeyPair,
+    ) -> RelayId {
         let mut dir = directory.write().unwrap();
 
-        // Find an unused port and relay ID
-        let (mut port, id) = (Self::random_high_port(), dir.next_relay_id);
+        // Find an unused port
+        let mut port = Self::random_high_port();
         while dir.used_ports.contains(&port) {
             port = Self::random_high_port();
         }
-        dir.used_ports.insert(port);
 
-        // Construct a new relay and add it to the directory
-        let relay = Relay::new(id, port, directory.clone());
+        let relay = Relay::with_id_key(dir.next_relay_id, port, directory.clone(), id_key);
+        dir.add_relay(relay)
+    }
+
+    /// Generate a new relay on the port and with the identity key named in a config file and
+    /// return its ID.
+    pub fn generate_relay_from_config(
+        directory: Arc<RwLock<Directory>>,
+        config: &OnionConfig,
+    ) -> Result<RelayId, OnionError> {
+        let mut dir = directory.write().unwrap();
+
+        if dir.used_ports.contains(&config.port) {
+            return Err(OnionError::PortInUse(config.port));
+        }
+
+        let relay = Relay::from_config(dir.next_relay_id, config, directory.clone())?;
+        Ok(dir.add_relay(relay))
+    }
+
+    /// Add a newly constructed relay to the directory, start it, and return its ID.
+    fn add_relay(&mut self, relay: Relay) -> RelayId {
+        let id = relay.id;
+        self.used_ports.insert(relay.port);
         let relay_info = RelayInfo {
             id,
-            port,
+            port: relay.port,
             id_key_pub: relay.id_key.public.clone(),
         };
-        dir.relays.insert(id, relay_info);
+        self.relays.insert(id, relay_info);
 
         // Increment the next relay ID
-        dir.next_relay_id += 1;
+        self.next_relay_id += 1;
 
         // Start the relay's listener thread
         relay.start_packet_handler();
@@ -91,23 +136,32 @@ impl Directory {
         self.relays.get(&id)
     }
 
-    /// Get a random relay from the directory.
-    pub fn get_random_relay(&self, exclude_list: HashSet<RelayId>) -> Option<&RelayInfo> {
-        if self.relays.is_empty() {
-            return None;
-        }
-
-        let keys: Vec<&RelayId> = self.relays.keys().collect();
-        let mut rng = rand::thread_rng();
+    /// Get the public info for every relay in the directory, sorted by ID.
+    pub fn relays(&self) -> Vec<&RelayInfo> {
+        let mut relays: Vec<&RelayInfo> = self.relays.values().collect();
+        relays.sort_by_key(|relay| relay.id);
+        relays
+    }
 
-        let random_key: &RelayId = {
-            let mut key = keys[rng.gen_range(0..keys.len())];
-            while exclude_list.contains(key) {
-                key = keys[rng.gen_range(0..keys.len())];
-            }
-            key
-        };
+    /// Get the number of relays in the directory.
+    pub fn num_relays(&self) -> usize {
+        self.relays.len()
+    }
 
-        self.relays.get(random_key)
+    /// Get a random relay from the directory that isn't in the exclude list, or None if every
+    /// relay is excluded.
+    pub fn get_random_relay(&self, exclude_list: HashSet<RelayId>) -> Option<&RelayInfo> {
+        // Sort the candidates so that a seeded directory picks the same relays on every run
+        let mut candidates: Vec<&RelayId> = self
+            .relays
+            .keys()
+            .filter(|id| !exclude_list.contains(id))
+            .collect();
+        candidates.sort();
+        let mut rng = self.rng.lock().unwrap();
+
+        candidates
+            .choose(&mut *rng)
+            .and_then(|id| self.relays.get(id))
     }
 }
@@ -0,0 +1,56 @@
+use ntru::NtruError;
+use thiserror::Error;
+
+/// Errors produced by the onion crate
+#[derive(Debug, Error)]
+pub enum OnionError {
+    /// An error from the NTRU layer of a message
+    #[error(transparent)]
+    Ntru(#[from] NtruError),
+    /// An error from the RSA onion layers of a message
+    #[error("rsa error: {0}")]
+    Rsa(#[from] rsa_ext::errors::Error),
+    /// A serialized RSA public key whose modulus isn't the size of an onion key
+    #[error("RSA modulus is {bits} bits, expected {expected}")]
+    InvalidModulus { bits: usize, expected: usize },
+    /// An I/O error on the TCP connection underlying a channel
+    #[error("channel i/o error: {0}")]
+    Io(#[from] std::io::Error),
+    /// A buffer was too short to hold the field being deserialized
+    #[error("truncated {what}: expected at least {expected} bytes, got {actual}")]
+    Truncated {
+        what: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    /// A packet declared a message longer than any NTRU ciphertext
+    #[error("message of {len} bytes exceeds the {max}-byte limit")]
+    MessageTooLong { len: usize, max: usize },
+    /// A message carried a type byte we don't know about
+    #[error("unknown message type {0}")]
+    UnknownMessageType(u8),
+    /// A relay message carried a payload type byte we don't know about
+    #[error("unknown relay payload type {0}")]
+    UnknownPayloadType(u8),
+    /// A node received a message that doesn't fit the current protocol step
+    #[error("unexpected message while {0}")]
+    UnexpectedMessage(&'static str),
+    /// A config file line couldn't be parsed
+    #[error("invalid config on line {line}: {reason}")]
+    InvalidConfigLine { line: usize, reason: String },
+    /// A config file is missing a required directive
+    #[error("config is missing required directive \"{0}\"")]
+    MissingConfigField(&'static str),
+    /// A relay was configured with a port another relay already listens on
+    #[error("port {0} is already used by another relay")]
+    PortInUse(u16),
+    /// A circuit ID that the node has no circuit for
+    #[error("no circuit with ID {0}")]
+    UnknownCircuit(u32),
+    /// A key store key name that isn't a plain file name
+    #[error("invalid key name {0:?}; names can't be empty or contain path separators or \"..\"")]
+    InvalidKeyName(String),
+    /// The directory has no relay left to choose from
+    #[error("no relays available in the directory")]
+    NoRelays,
+}
@@ -0,0 +1,159 @@
+use crate::OnionError;
+use ntru::{NtruKeyPair, NtruPrivateKey, NtruPublicKey};
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{self, Path, PathBuf};
+
+/// File extension of NTRU private keys kept in a key store
+const KEY_EXTENSION: &str = "ntru";
+/// Name of a node's long-lived NTRU identity key
+pub const IDENTITY_KEY: &str = "identity";
+/// Key files hold private keys, so only their owner may read or write them
+#[cfg(unix)]
+const KEY_FILE_MODE: u32 = 0o600;
+
+/// A named key in a key store along with its public key fingerprint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyInfo {
+    pub name: String,
+    pub fingerprint: String,
+}
+
+/// A directory on disk holding a node's NTRU keys, one `<name>.ntru` file per key, so that
+/// relays and hosts keep the same identity across restarts.
+///
+/// RSA onion keys are deliberately left out: they are generated per circuit and never touch disk.
+pub struct KeyStore {
+    dir: PathBuf,
+}
+
+impl KeyStore {
+    /// Open the key store in `dir`, creating the directory if it doesn't exist.
+    pub fn open(dir: impl AsRef<Path>) -> Result<KeyStore, OnionError> {
+        fs::create_dir_all(&dir)?;
+        Ok(KeyStore {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    /// Path of the file holding the key called `name`. Names must be plain file names so that keys
+    /// can't be read or written outside the store.
+    pub fn path(&self, name: &str) -> Result<PathBuf, OnionError> {
+        if name.is_empty() || name.contains("..") || name.chars().any(path::is_separator) {
+            return Err(OnionError::InvalidKeyName(name.to_string()));
+        }
+        Ok(self.dir.join(format!("{name}.{KEY_EXTENSION}")))
+    }
+
+    /// Load the key called `name`, or None if the store doesn't have it.
+    pub fn load(&self, name: &str) -> Result<Option<NtruKeyPair>, OnionError> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(load_key(path)?))
+    }
+
+    /// Load the key called `name`, generating and saving a new one if the store doesn't have it.
+    pub fn load_or_generate(&self, name: &str) -> Result<NtruKeyPair, OnionError> {
+        load_or_generate_key(self.path(name)?)
+    }
+
+    /// Replace the key called `name` with a freshly generated one. The previous key is kept
+    /// alongside it as `<name>.ntru.old` until the next rotation.
+    pub fn rotate(&self, name: &str) -> Result<NtruKeyPair, OnionError> {
+        let path = self.path(name)?;
+        // Write the new key out first so a failed save leaves the current key in place
+        let keypair = NtruKeyPair::new();
+        let tmp_path = path.with_extension(format!("{KEY_EXTENSION}.tmp"));
+        if let Err(e) = save_key(&tmp_path, &keypair) {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(e);
+        }
+
+        if path.exists() {
+            let old_path = path.with_extension(format!("{KEY_EXTENSION}.old"));
+            fs::rename(&path, &old_path)?;
+            restrict_permissions(&old_path)?;
+        }
+        fs::rename(&tmp_path, &path)?;
+        Ok(keypair)
+    }
+
+    /// List every key in the store with its fingerprint, sorted by name. Key files that can't be
+    /// read are skipped with a message naming the file.
+    pub fn list(&self) -> Result<Vec<KeyInfo>, OnionError> {
+        let mut keys = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(KEY_EXTENSION) {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            match load_key(&path) {
+                Ok(keypair) => keys.push(KeyInfo {
+                    name: name.to_string(),
+                    fingerprint: fingerprint(&keypair.public),
+                }),
+                Err(e) => eprintln!("Skipping unreadable key file {}: {e}", path.display()),
+            }
+        }
+        keys.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(keys)
+    }
+}
+
+/// Load an NTRU key pair from the private key file at `path`.
+pub fn load_key(path: impl AsRef<Path>) -> Result<NtruKeyPair, OnionError> {
+    let private = NtruPrivateKey::from_be_bytes(&fs::read(path)?)?;
+    Ok(NtruKeyPair::from_private(private))
+}
+
+/// Save the private half of an NTRU key pair to `path`, readable only by its owner.
+pub fn save_key(path: impl AsRef<Path>, keypair: &NtruKeyPair) -> Result<(), OnionError> {
+    let path = path.as_ref();
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(KEY_FILE_MODE);
+
+    let mut file = options.open(path)?;
+    // The mode only applies to new files, so also tighten a key file that already existed
+    restrict_permissions(path)?;
+    file.write_all(&keypair.private.to_be_bytes())?;
+    Ok(())
+}
+
+/// Load the key pair at `path`, generating and saving a new one if the file doesn't exist.
+pub fn load_or_generate_key(path: impl AsRef<Path>) -> Result<NtruKeyPair, OnionError> {
+    let path = path.as_ref();
+    if path.exists() {
+        return load_key(path);
+    }
+    let keypair = NtruKeyPair::new();
+    save_key(path, &keypair)?;
+    Ok(keypair)
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) -> io::Result<()> {
+    fs::set_permissions(path, fs::Permissions::from_mode(KEY_FILE_MODE))
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// The SHA-256 fingerprint of a public key as lowercase hex, for comparing keys out of band.
+pub fn fingerprint(key: &NtruPublicKey) -> String {
+    Sha256::digest(key.to_be_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
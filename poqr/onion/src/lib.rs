@@ -1,16 +1,24 @@
 // Module: onion
 mod channel;
+mod config;
 mod directory;
+mod error;
+mod keystore;
 mod messages;
 mod nodes;
 mod rsa_utils;
 mod tables;
 // Exported from onion module
 pub use channel::Channel;
+pub use config::{OnionConfig, DEFAULT_CIRCUIT_LENGTH};
 pub use directory::{Directory, RelayId, RelayInfo};
+pub use error::OnionError;
+pub use keystore::{
+    fingerprint, load_key, load_or_generate_key, save_key, KeyInfo, KeyStore, IDENTITY_KEY,
+};
 pub use messages::{
-    BeginPayload, CreatedPayload, ExtendPayload, ExtendedPayload, Message, OnionHeader,
-    OnionPacket, RelayPayload,
+    BeginPayload, CreatePayload, CreatedPayload, DataPayload, ExtendPayload, ExtendedPayload,
+    Message, OnionHeader, OnionPacket, RelayPayload, MAX_MESSAGE_LEN,
 };
 pub use nodes::{Host, Relay};
 pub use rsa_utils::{from_be_bytes, to_be_bytes};
@@ -1,11 +1,17 @@
 use ntru::convolution_polynomial::ConvPoly;
 use ntru::ntru_key::{NtruPrivateKey, NtruPublicKey};
+use ntru::params::N;
 use rsa_ext::{PaddingScheme, PublicKey, RsaPrivateKey, RsaPublicKey};
 
+use crate::OnionError;
+
 use super::payloads::{
     BeginPayload, CreatePayload, CreatedPayload, DataPayload, ExtendPayload, ExtendedPayload,
 };
 
+/// Largest serialized message: an NTRU ciphertext has at most N 4-byte coefficients
+pub const MAX_MESSAGE_LEN: usize = N * 4;
+
 /// A packet sent over the POQR network
 pub struct OnionPacket {
     pub header: OnionHeader,
@@ -14,16 +20,20 @@ pub struct OnionPacket {
 
 impl OnionPacket {
     /// Serialize an OnionPacket into a big-endian byte array.
-    pub fn to_be_bytes(&self, id_key: NtruPublicKey, onion_keys: Vec<RsaPublicKey>) -> Vec<u8> {
+    pub fn to_be_bytes(
+        &self,
+        id_key: NtruPublicKey,
+        onion_keys: Vec<RsaPublicKey>,
+    ) -> Result<Vec<u8>, OnionError> {
         let mut buf = Vec::new();
 
-        let msg_bytes = self.msg.to_be_bytes(id_key, onion_keys);
+        let msg_bytes = self.msg.to_be_bytes(id_key, onion_keys)?;
         let msg_len: u32 = msg_bytes.len() as u32;
 
         buf.extend_from_slice(&self.header.circ_id.to_be_bytes());
         buf.extend_from_slice(&msg_len.to_be_bytes());
         buf.extend_from_slice(&msg_bytes);
-        buf
+        Ok(buf)
     }
 
     /// Deserialize an OnionPacket from a big-endian byte array.
@@ -31,13 +41,33 @@ impl OnionPacket {
         buf: &[u8],
         id_key: NtruPrivateKey,
         onion_keys: Vec<RsaPrivateKey>,
-    ) -> OnionPacket {
+    ) -> Result<OnionPacket, OnionError> {
+        if buf.len() < 8 {
+            return Err(OnionError::Truncated {
+                what: "packet header",
+                expected: 8,
+                actual: buf.len(),
+            });
+        }
         let header = OnionHeader {
             circ_id: u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]),
         };
         let msg_len = u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]]) as usize;
-        let msg = Message::from_be_bytes(buf[8..8 + msg_len].to_vec(), id_key, onion_keys);
-        OnionPacket { header, msg }
+        if msg_len > MAX_MESSAGE_LEN {
+            return Err(OnionError::MessageTooLong {
+                len: msg_len,
+                max: MAX_MESSAGE_LEN,
+            });
+        }
+        if buf.len() - 8 < msg_len {
+            return Err(OnionError::Truncated {
+                what: "packet message",
+                expected: msg_len,
+                actual: buf.len() - 8,
+            });
+        }
+        let msg = Message::from_be_bytes(buf[8..8 + msg_len].to_vec(), id_key, onion_keys)?;
+        Ok(OnionPacket { header, msg })
     }
 }
 
@@ -75,53 +105,63 @@ pub enum RelayPayload {
 
 impl Message {
     /// Adds a layer of NTRU encryption to a Vec<u8> using a valid NTRU public key then serializes it to a new byte vector
-    fn add_quantum_onion_skin(bytes: &[u8], id_key: NtruPublicKey) -> Vec<u8> {
-        let poly = id_key.encrypt_bytes(bytes.to_vec());
-        poly.to_be_bytes()
+    fn add_quantum_onion_skin(bytes: &[u8], id_key: NtruPublicKey) -> Result<Vec<u8>, OnionError> {
+        let poly = id_key.try_encrypt_bytes(bytes.to_vec())?;
+        Ok(poly.to_be_bytes())
     }
 
     /// Deserializes a serialized NTRU encrypted message, unencrypts it, then reserializes it to a vector of bytes.
-    fn remove_quantum_onion_skin(bytes: &[u8], id_key: NtruPrivateKey) -> Vec<u8> {
-        let poly = ConvPoly::from_be_bytes(&bytes.to_vec());
-        id_key.decrypt_to_bytes(poly)
+    fn remove_quantum_onion_skin(
+        bytes: &[u8],
+        id_key: NtruPrivateKey,
+    ) -> Result<Vec<u8>, OnionError> {
+        let poly = ConvPoly::from_be_bytes(bytes)?;
+        Ok(id_key.decrypt_to_bytes(poly))
     }
 
-    fn add_onion_skin(bytes: &[u8], onion_keys: Vec<RsaPublicKey>) -> Vec<u8> {
+    fn add_onion_skin(bytes: &[u8], onion_keys: Vec<RsaPublicKey>) -> Result<Vec<u8>, OnionError> {
         if onion_keys.is_empty() {
             // No onion keys, return the original bytes
-            bytes.to_vec()
+            Ok(bytes.to_vec())
         } else {
             let padding = PaddingScheme::new_pkcs1v15_encrypt();
             let mut rng = rand::thread_rng();
             // Encrypt the message with the first onion key
-            let mut enc = onion_keys[0].encrypt(&mut rng, padding, bytes).unwrap();
+            let mut enc = onion_keys[0].encrypt(&mut rng, padding, bytes)?;
             // Encrypt the message with the rest of the onion keys
             for i in 1..onion_keys.len() {
                 let padding = PaddingScheme::new_pkcs1v15_encrypt();
-                enc = onion_keys[i].encrypt(&mut rng, padding, &enc).unwrap();
+                enc = onion_keys[i].encrypt(&mut rng, padding, &enc)?;
             }
-            enc
+            Ok(enc)
         }
     }
 
-    fn remove_onion_skin(bytes: &[u8], onion_keys: Vec<RsaPrivateKey>) -> Vec<u8> {
+    fn remove_onion_skin(
+        bytes: &[u8],
+        onion_keys: Vec<RsaPrivateKey>,
+    ) -> Result<Vec<u8>, OnionError> {
         if onion_keys.is_empty() {
             // No onion keys, return the original bytes
-            bytes.to_vec()
+            Ok(bytes.to_vec())
         } else {
             let padding = PaddingScheme::new_pkcs1v15_encrypt();
             // Decrypt the message with the last onion key
-            let mut dec = onion_keys[0].decrypt(padding, bytes).unwrap();
+            let mut dec = onion_keys[0].decrypt(padding, bytes)?;
             // Decrypt the message with the rest of the onion keys
             for i in 1..onion_keys.len() {
                 let padding = PaddingScheme::new_pkcs1v15_encrypt();
-                dec = onion_keys[i].decrypt(padding, &dec).unwrap();
+                dec = onion_keys[i].decrypt(padding, &dec)?;
             }
-            dec
+            Ok(dec)
         }
     }
 
-    pub fn to_be_bytes(&self, id_key: NtruPublicKey, onion_keys: Vec<RsaPublicKey>) -> Vec<u8> {
+    pub fn to_be_bytes(
+        &self,
+        id_key: NtruPublicKey,
+        onion_keys: Vec<RsaPublicKey>,
+    ) -> Result<Vec<u8>, OnionError> {
         let mut buf = Vec::new();
 
         match self {
@@ -139,22 +179,22 @@ impl Message {
                 match payload {
                     RelayPayload::Extend(payload) => {
                         buf.push(PAYLOAD_EXTEND);
-                        let onion = Message::add_onion_skin(&payload.to_be_bytes(), onion_keys);
+                        let onion = Message::add_onion_skin(&payload.to_be_bytes(), onion_keys)?;
                         buf.extend_from_slice(&onion);
                     }
                     RelayPayload::Extended(payload) => {
                         buf.push(PAYLOAD_EXTENDED);
-                        let onion = Message::add_onion_skin(&payload.to_be_bytes(), onion_keys);
+                        let onion = Message::add_onion_skin(&payload.to_be_bytes(), onion_keys)?;
                         buf.extend_from_slice(&onion);
                     }
                     RelayPayload::Begin(payload) => {
                         buf.push(PAYLOAD_BEGIN);
-                        let onion = Message::add_onion_skin(&payload.to_be_bytes(), onion_keys);
+                        let onion = Message::add_onion_skin(&payload.to_be_bytes(), onion_keys)?;
                         buf.extend_from_slice(&onion);
                     }
                     RelayPayload::Data(payload) => {
                         buf.push(PAYLOAD_DATA);
-                        let onion = Message::add_onion_skin(&payload.to_be_bytes(), onion_keys);
+                        let onion = Message::add_onion_skin(&payload.to_be_bytes(), onion_keys)?;
                         buf.extend_from_slice(&onion);
                     }
                 }
@@ -167,36 +207,48 @@ impl Message {
         msg: Vec<u8>,
         id_key: NtruPrivateKey,
         onion_keys: Vec<RsaPrivateKey>,
-    ) -> Message {
-        let msg = Message::remove_quantum_onion_skin(&msg, id_key);
-
-        match msg[0] {
-            MESSAGE_CREATE => Message::Create(CreatePayload::from_be_bytes(&msg[1..])),
-            MESSAGE_CREATED => Message::Created(CreatedPayload::from_be_bytes(&msg[1..])),
-            MESSAGE_RELAY => match msg[1] {
-                PAYLOAD_EXTEND => {
-                    let payload_bytes = Message::remove_onion_skin(&msg[2..], onion_keys);
-                    let payload = ExtendPayload::from_be_bytes(&payload_bytes);
-                    Message::Relay(RelayPayload::Extend(payload))
+    ) -> Result<Message, OnionError> {
+        let msg = Message::remove_quantum_onion_skin(&msg, id_key)?;
+
+        match msg.first() {
+            Some(&MESSAGE_CREATE) => Ok(Message::Create(CreatePayload::from_be_bytes(&msg[1..])?)),
+            Some(&MESSAGE_CREATED) => {
+                Ok(Message::Created(CreatedPayload::from_be_bytes(&msg[1..])?))
+            }
+            Some(&MESSAGE_RELAY) => match msg.get(1) {
+                Some(&PAYLOAD_EXTEND) => {
+                    let payload_bytes = Message::remove_onion_skin(&msg[2..], onion_keys)?;
+                    let payload = ExtendPayload::from_be_bytes(&payload_bytes)?;
+                    Ok(Message::Relay(RelayPayload::Extend(payload)))
                 }
-                PAYLOAD_EXTENDED => {
-                    let payload_bytes = Message::remove_onion_skin(&msg[2..], onion_keys);
-                    let payload = ExtendedPayload::from_be_bytes(&payload_bytes);
-                    Message::Relay(RelayPayload::Extended(payload))
+                Some(&PAYLOAD_EXTENDED) => {
+                    let payload_bytes = Message::remove_onion_skin(&msg[2..], onion_keys)?;
+                    let payload = ExtendedPayload::from_be_bytes(&payload_bytes)?;
+                    Ok(Message::Relay(RelayPayload::Extended(payload)))
                 }
-                PAYLOAD_BEGIN => {
-                    let payload_bytes = Message::remove_onion_skin(&msg[2..], onion_keys);
-                    let payload = BeginPayload::from_be_bytes(&payload_bytes);
-                    Message::Relay(RelayPayload::Begin(payload))
+                Some(&PAYLOAD_BEGIN) => {
+                    let payload_bytes = Message::remove_onion_skin(&msg[2..], onion_keys)?;
+                    let payload = BeginPayload::from_be_bytes(&payload_bytes)?;
+                    Ok(Message::Relay(RelayPayload::Begin(payload)))
                 }
-                PAYLOAD_DATA => {
-                    let payload_bytes = Message::remove_onion_skin(&msg[2..], onion_keys);
+                Some(&PAYLOAD_DATA) => {
+                    let payload_bytes = Message::remove_onion_skin(&msg[2..], onion_keys)?;
                     let payload = DataPayload::from_be_bytes(&payload_bytes);
-                    Message::Relay(RelayPayload::Data(payload))
+                    Ok(Message::Relay(RelayPayload::Data(payload)))
                 }
-                _ => panic!("Unknown payload type"),
+                Some(&payload_type) => Err(OnionError::UnknownPayloadType(payload_type)),
+                None => Err(OnionError::Truncated {
+                    what: "relay message",
+                    expected: 2,
+                    actual: msg.len(),
+                }),
             },
-            _ => panic!("Unknown message type"),
+            Some(&msg_type) => Err(OnionError::UnknownMessageType(msg_type)),
+            None => Err(OnionError::Truncated {
+                what: "message",
+                expected: 1,
+                actual: 0,
+            }),
         }
     }
 }
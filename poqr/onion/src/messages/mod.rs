@@ -2,5 +2,7 @@
 mod message;
 mod payloads;
 // Exported from messages module
-pub use message::{Message, OnionHeader, OnionPacket, RelayPayload};
-pub use payloads::{BeginPayload, CreatePayload, CreatedPayload, ExtendPayload, ExtendedPayload};
+pub use message::{Message, OnionHeader, OnionPacket, RelayPayload, MAX_MESSAGE_LEN};
+pub use payloads::{
+    BeginPayload, CreatePayload, CreatedPayload, DataPayload, ExtendPayload, ExtendedPayload,
+};
@@ -1,3 +1,5 @@
+use crate::OnionError;
+
 pub struct BeginPayload {
     status: bool,
 }
@@ -9,9 +11,16 @@ impl BeginPayload {
     }
 
     /// Deserialize a CreatedPayload from a big-endian byte array.
-    pub fn from_be_bytes(buf: &[u8]) -> BeginPayload {
-        BeginPayload {
-            status: buf[0] != 0,
+    pub fn from_be_bytes(buf: &[u8]) -> Result<BeginPayload, OnionError> {
+        match buf.first() {
+            Some(&status) => Ok(BeginPayload {
+                status: status != 0,
+            }),
+            None => Err(OnionError::Truncated {
+                what: "BEGIN payload",
+                expected: 1,
+                actual: 0,
+            }),
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::{from_be_bytes, to_be_bytes};
+use crate::{from_be_bytes, to_be_bytes, OnionError};
 use rsa_ext::RsaPublicKey;
 
 pub struct CreatePayload {
@@ -13,9 +13,9 @@ impl CreatePayload {
     }
 
     /// Deserialize the CreatePayload from a big-endian byte array.
-    pub fn from_be_bytes(buf: &[u8]) -> CreatePayload {
-        CreatePayload {
-            public_key: from_be_bytes(buf),
-        }
+    pub fn from_be_bytes(buf: &[u8]) -> Result<CreatePayload, OnionError> {
+        Ok(CreatePayload {
+            public_key: from_be_bytes(buf)?,
+        })
     }
 }
@@ -1,5 +1,5 @@
 use crate::RelayId;
-use crate::{from_be_bytes, to_be_bytes};
+use crate::{from_be_bytes, to_be_bytes, OnionError};
 use rsa_ext::RsaPublicKey;
 
 pub struct ExtendPayload {
@@ -14,9 +14,9 @@ impl ExtendPayload {
     }
 
     /// Deserialize an ExtendPayload from a big-endian byte array.
-    pub fn from_be_bytes(buf: &[u8]) -> ExtendPayload {
-        ExtendPayload {
-            public_key: from_be_bytes(buf),
-        }
+    pub fn from_be_bytes(buf: &[u8]) -> Result<ExtendPayload, OnionError> {
+        Ok(ExtendPayload {
+            public_key: from_be_bytes(buf)?,
+        })
     }
 }
@@ -1,4 +1,4 @@
-use crate::{from_be_bytes, to_be_bytes};
+use crate::{from_be_bytes, to_be_bytes, OnionError};
 use rsa_ext::RsaPublicKey;
 
 pub struct ExtendedPayload {
@@ -13,9 +13,9 @@ impl ExtendedPayload {
     }
 
     /// Deserialize an ExtendedPayload from a big-endian byte array.
-    pub fn from_be_bytes(buf: &[u8]) -> ExtendedPayload {
-        ExtendedPayload {
-            public_key: from_be_bytes(buf),
-        }
+    pub fn from_be_bytes(buf: &[u8]) -> Result<ExtendedPayload, OnionError> {
+        Ok(ExtendedPayload {
+            public_key: from_be_bytes(buf)?,
+        })
     }
 }
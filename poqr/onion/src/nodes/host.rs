@@ -1,15 +1,19 @@
 use crate::messages::*;
-use crate::{Channel, ChannelTable, CircuitId, CircuitTable, Directory};
+use crate::{
+    load_or_generate_key, Channel, ChannelTable, CircuitId, CircuitTable, Directory, OnionConfig,
+    OnionError, RelayInfo, DEFAULT_CIRCUIT_LENGTH,
+};
 use ntru::ntru_key::NtruPublicKey;
 use ntru::NtruKeyPair;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use rsa_ext::{RsaPrivateKey, RsaPublicKey};
 use std::collections::HashSet;
 use std::{
-    net::TcpStream,
+    net::{Shutdown, TcpStream},
     sync::{mpsc, Arc, Mutex, RwLock},
 };
 
-const CIRCUIT_LENGTH: usize = 3;
 const LOCALHOST: &str = "127.0.0.1";
 
 pub struct Host {
@@ -27,10 +31,48 @@ pub struct Host {
     pub id_key: Arc<NtruKeyPair>,
     /// The public directory of relays
     pub directory: Arc<RwLock<Directory>>,
+    /// Random number generator used to pick circuit IDs
+    pub rng: Arc<Mutex<StdRng>>,
+    /// The number of relays in each circuit this host builds
+    pub circuit_length: usize,
 }
 
 impl Host {
     pub fn new(port: u16, directory: Arc<RwLock<Directory>>) -> Host {
+        Host::with_rng(port, directory, StdRng::from_entropy(), NtruKeyPair::new())
+    }
+
+    /// Create a host from a loaded config file. If the config names an identity key file, the
+    /// key is loaded from it (or generated and saved there on first start).
+    pub fn from_config(
+        config: &OnionConfig,
+        directory: Arc<RwLock<Directory>>,
+    ) -> Result<Host, OnionError> {
+        let id_key = match &config.id_key_path {
+            Some(path) => load_or_generate_key(path)?,
+            None => NtruKeyPair::new(),
+        };
+        let mut host = Host::with_rng(config.port, directory, StdRng::from_entropy(), id_key);
+        host.circuit_length = config.circuit_length;
+        Ok(host)
+    }
+
+    /// Create a host whose circuit IDs are reproducible from the given seed.
+    pub fn with_seed(port: u16, directory: Arc<RwLock<Directory>>, seed: u64) -> Host {
+        Host::with_rng(
+            port,
+            directory,
+            StdRng::seed_from_u64(seed),
+            NtruKeyPair::new(),
+        )
+    }
+
+    fn with_rng(
+        port: u16,
+        directory: Arc<RwLock<Directory>>,
+        rng: StdRng,
+        id_key: NtruKeyPair,
+    ) -> Host {
         let (sender, receiver) = mpsc::channel();
 
         Host {
@@ -39,31 +81,38 @@ impl Host {
             packet_receiver: Arc::new(Mutex::new(receiver)),
             channels: Arc::new(Mutex::new(ChannelTable::new())),
             circuit_table: Arc::new(Mutex::new(CircuitTable::new())),
-            id_key: Arc::new(NtruKeyPair::new()),
+            id_key: Arc::new(id_key),
             directory,
+            rng: Arc::new(Mutex::new(rng)),
+            circuit_length: DEFAULT_CIRCUIT_LENGTH,
         }
     }
 
-    fn generate_onion_keys(bits: usize) -> (Vec<RsaPublicKey>, Vec<RsaPrivateKey>) {
+    fn generate_onion_keys(
+        count: usize,
+        bits: usize,
+    ) -> Result<(Vec<RsaPublicKey>, Vec<RsaPrivateKey>), OnionError> {
         let mut rng = rand::thread_rng();
         let (mut public_keys, mut private_keys) = (Vec::new(), Vec::new());
 
-        for _ in 0..CIRCUIT_LENGTH {
-            let private_key = RsaPrivateKey::new(&mut rng, bits).unwrap();
+        for _ in 0..count {
+            let private_key = RsaPrivateKey::new(&mut rng, bits)?;
             let public_key = RsaPublicKey::from(&private_key);
             public_keys.push(public_key);
             private_keys.push(private_key);
         }
 
-        (public_keys, private_keys)
+        Ok((public_keys, private_keys))
     }
 
-    fn generate_new_circuit_id(&self) -> CircuitId {
+    /// Pick a random circuit ID that is not already in use.
+    pub fn generate_new_circuit_id(&self) -> CircuitId {
         let circuits = self.circuit_table.lock().unwrap();
+        let mut rng = self.rng.lock().unwrap();
 
-        let mut circuit_id = rand::random::<u32>();
+        let mut circuit_id = rng.gen::<u32>();
         while circuits.used_circuit_ids.contains(&circuit_id) {
-            circuit_id = rand::random::<u32>();
+            circuit_id = rng.gen::<u32>();
         }
 
         circuit_id
@@ -75,9 +124,9 @@ impl Host {
         port: u16,
         id_key: NtruPublicKey,
         onion_keys: Vec<RsaPrivateKey>,
-    ) {
+    ) -> Result<(), OnionError> {
         let mut channels = self.channels.lock().unwrap();
-        let connection = TcpStream::connect(format!("{LOCALHOST}:{port}")).unwrap();
+        let connection = TcpStream::connect(format!("{LOCALHOST}:{port}"))?;
         // Instantiate channel
         let channel = Channel {
             forward_id_key: Arc::new(id_key),
@@ -88,15 +137,16 @@ impl Host {
             packet_sender: (*self.packet_sender).clone(),
         };
         channels.insert(circuit_id, channel);
+        Ok(())
     }
 
-    pub fn create_circuit(&mut self, destination: u16) -> CircuitId {
-        // Lock the tables
-        let mut circuits = self.circuit_table.lock().unwrap();
-        let mut channels = self.channels.lock().unwrap();
-        // Generate
+    pub fn create_circuit(&mut self, destination: u16) -> Result<CircuitId, OnionError> {
+        // Fail before generating keys if the directory can't fill every hop with a distinct relay
+        if self.directory.read().unwrap().num_relays() < self.circuit_length {
+            return Err(OnionError::NoRelays);
+        }
         // Generate ephemeral key pairs for backward communication from each relay
-        let (public_keys, private_keys) = Host::generate_onion_keys(1024);
+        let (public_keys, private_keys) = Host::generate_onion_keys(self.circuit_length, 1024)?;
         // Exclude list to avoid using the same relay twice
         let mut exclude_list: HashSet<u32> = HashSet::new();
 
@@ -104,7 +154,9 @@ impl Host {
         let circuit_id = self.generate_new_circuit_id();
         let relay = {
             let dir = self.directory.read().unwrap();
-            dir.get_random_relay(exclude_list.clone()).unwrap().clone()
+            dir.get_random_relay(exclude_list.clone())
+                .ok_or(OnionError::NoRelays)?
+                .clone()
         };
         exclude_list.insert(relay.id);
 
@@ -114,7 +166,9 @@ impl Host {
             relay.port,
             relay.id_key_pub,
             private_keys.clone(),
-        );
+        )?;
+        // Lock the channel table only once the helpers above, which lock it themselves, are done
+        let mut channels = self.channels.lock().unwrap();
         let channel = channels.get_mut(circuit_id).unwrap();
 
         // Send the CREATE message to the first relay
@@ -122,48 +176,93 @@ impl Host {
             public_key: public_keys[0].clone(), // The public onion key for this relay to encrypt backward messages
         };
         let create_message = Message::Create(create_payload);
-        channel.send(circuit_id, create_message);
+        channel.send(circuit_id, create_message)?;
 
         // Wait for the CREATED message
-        let response = channel.recv();
+        let response = channel.recv()?;
         match response.msg {
             Message::Created(payload) => {
                 let mut forward_onion_keys = channel.forward_onion_keys.lock().unwrap();
                 forward_onion_keys.push(payload.public_key);
             }
-            _ => panic!("Unexpected message while creating circuit"),
+            _ => return Err(OnionError::UnexpectedMessage("creating circuit")),
         }
 
         // Extend the circuit to additional relays
-        for i in 1..CIRCUIT_LENGTH {
+        for public_key in public_keys.iter().skip(1) {
             // Select the next relay, avoiding duplicates
             let relay = {
                 let dir = self.directory.read().unwrap();
-                dir.get_random_relay(exclude_list.clone()).unwrap().clone()
+                dir.get_random_relay(exclude_list.clone())
+                    .ok_or(OnionError::NoRelays)?
+                    .clone()
             };
             exclude_list.insert(relay.id);
 
             // Send EXTEND message
             let extend_payload = ExtendPayload {
-                public_key: public_keys[i].clone(),
+                public_key: public_key.clone(),
             };
             let extend_message = Message::Relay(RelayPayload::Extend(extend_payload));
-            channel.send(circuit_id, extend_message);
+            channel.send(circuit_id, extend_message)?;
 
             // Wait for EXTENDED message
-            let response = channel.recv();
+            let response = channel.recv()?;
             match response.msg {
                 Message::Relay(RelayPayload::Extended(payload)) => {
                     // Successfully extended to the next relay
                     let mut forward_onion_keys = channel.forward_onion_keys.lock().unwrap();
                     forward_onion_keys.push(payload.public_key);
                 }
-                _ => panic!("Unexpected message while extending circuit"),
+                _ => return Err(OnionError::UnexpectedMessage("extending circuit")),
             }
         }
 
         // At this point, the circuit is fully established
+        let mut circuits = self.circuit_table.lock().unwrap();
         circuits.insert(destination, circuit_id);
-        circuit_id
+        Ok(circuit_id)
+    }
+
+    /// List the relays in the directory, sorted by ID.
+    pub fn list_relays(&self) -> Vec<RelayInfo> {
+        let dir = self.directory.read().unwrap();
+        dir.relays().into_iter().cloned().collect()
+    }
+
+    /// List this host's circuits as (destination port, circuit ID) pairs, sorted by port.
+    pub fn list_circuits(&self) -> Vec<(u16, CircuitId)> {
+        let circuits = self.circuit_table.lock().unwrap();
+        let mut list: Vec<(u16, CircuitId)> = circuits
+            .circuits
+            .iter()
+            .map(|(&port, &circuit_id)| (port, circuit_id))
+            .collect();
+        list.sort();
+        list
+    }
+
+    /// Tear down a circuit, forgetting its destination and closing its channel to the first relay.
+    /// There is no DESTROY message yet, so the relays only notice once the connection closes.
+    pub fn destroy_circuit(&mut self, circuit_id: CircuitId) -> Result<(), OnionError> {
+        let mut circuits = self.circuit_table.lock().unwrap();
+        let port = circuits
+            .circuits
+            .iter()
+            .find(|(_, &id)| id == circuit_id)
+            .map(|(&port, _)| port)
+            .ok_or(OnionError::UnknownCircuit(circuit_id))?;
+        circuits.remove(port);
+
+        let mut channels = self.channels.lock().unwrap();
+        if let Some(channel) = channels.remove(circuit_id) {
+            // Shutting down also stops the channel's listener thread, which holds a clone
+            channel
+                .connection
+                .lock()
+                .unwrap()
+                .shutdown(Shutdown::Both)?;
+        }
+        Ok(())
     }
 }
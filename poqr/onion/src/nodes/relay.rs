@@ -1,6 +1,7 @@
 use crate::{
-    BeginPayload, ChannelTable, CreatePayload, CreatedPayload, Directory, ExtendPayload,
-    ExtendedPayload, Message, OnionPacket, RelayPayload,
+    load_or_generate_key, BeginPayload, ChannelTable, CircuitId, CreatePayload, CreatedPayload,
+    DataPayload, Directory, ExtendPayload, ExtendedPayload, Message, OnionConfig, OnionError,
+    OnionPacket, RelayPayload,
 };
 use ntru::NtruKeyPair;
 use std::net::TcpListener;
@@ -26,6 +27,30 @@ pub struct Relay {
 
 impl Relay {
     pub fn new(id: u32, port: u16, directory: Arc<RwLock<Directory>>) -> Relay {
+        Relay::with_id_key(id, port, directory, NtruKeyPair::new())
+    }
+
+    /// Create a relay from a loaded config file. If the config names an identity key file, the
+    /// key is loaded from it (or generated and saved there on first start).
+    pub fn from_config(
+        id: u32,
+        config: &OnionConfig,
+        directory: Arc<RwLock<Directory>>,
+    ) -> Result<Relay, OnionError> {
+        let id_key = match &config.id_key_path {
+            Some(path) => load_or_generate_key(path)?,
+            None => NtruKeyPair::new(),
+        };
+        Ok(Relay::with_id_key(id, config.port, directory, id_key))
+    }
+
+    /// Create a relay with an existing identity key, e.g. one loaded from a key store.
+    pub fn with_id_key(
+        id: u32,
+        port: u16,
+        directory: Arc<RwLock<Directory>>,
+        id_key: NtruKeyPair,
+    ) -> Relay {
         let (sender, receiver) = mpsc::channel();
 
         Relay {
@@ -34,7 +59,7 @@ impl Relay {
             packet_sender: Arc::new(sender),
             packet_receiver: Arc::new(Mutex::new(receiver)),
             channels: Arc::new(Mutex::new(ChannelTable::new())),
-            id_key: Arc::new(NtruKeyPair::new()),
+            id_key: Arc::new(id_key),
             directory,
         }
     }
@@ -69,14 +94,16 @@ impl Relay {
     }
 
     fn handle_packet(&self, packet: OnionPacket) {
+        let circ_id = packet.header.circ_id;
+
         match packet.msg {
             Message::Create(create_payload) => {
                 println!("Received CREATE request");
-                self.handle_create(create_payload);
+                self.handle_create(circ_id, create_payload);
             }
             Message::Created(created_payload) => {
                 println!("Received CREATED confirmation");
-                self.handle_created(created_payload);
+                self.handle_created(circ_id, created_payload);
             }
             Message::Relay(payload) => match payload {
                 RelayPayload::Data(data) => {
@@ -100,19 +127,25 @@ impl Relay {
         }
     }
 
-    fn handle_create(&self, payload: CreatePayload) {
+    fn handle_create(&self, circ_id: CircuitId, payload: CreatePayload) {
         // Get the channel for the circuit
         let mut channels = self.channels.lock().unwrap();
-        let channel = channels.get_mut(&payload.circuit_id).unwrap();
-        // Add the backward onion key to the channel
-        let mut backward_onion_keys = channel.backward_onion_keys.lock().unwrap();
-        backward_onion_keys.push(payload.public_key);
+        let Some(channel) = channels.get_mut(circ_id) else {
+            eprintln!("No channel for circuit {circ_id}");
+            return;
+        };
+        // Messages sent back towards the host are encrypted with its onion key
+        let mut forward_onion_keys = channel.forward_onion_keys.lock().unwrap();
+        forward_onion_keys.push(payload.public_key);
     }
 
-    fn handle_created(&self, payload: CreatedPayload) {
+    fn handle_created(&self, circ_id: CircuitId, payload: CreatedPayload) {
         // Get the channel for the circuit
         let mut channels = self.channels.lock().unwrap();
-        let channel = channels.get_mut(&payload.circuit_id).unwrap();
+        let Some(channel) = channels.get_mut(circ_id) else {
+            eprintln!("No channel for circuit {circ_id}");
+            return;
+        };
         // Add the forward onion key to the channel
         let mut forward_onion_keys = channel.forward_onion_keys.lock().unwrap();
         forward_onion_keys.push(payload.public_key);
@@ -134,7 +167,7 @@ impl Relay {
         todo!()
     }
     //TODO: SCRAPPED DUE TO TIMEFRAME
-    fn handle_data(&self, data: Vec<u8>) {
+    fn handle_data(&self, data: DataPayload) {
         eprintln!("This would be implemented if we had more time!");
         todo!()
     }
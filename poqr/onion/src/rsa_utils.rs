@@ -1,5 +1,9 @@
+use crate::OnionError;
 use rsa_ext::{BigUint, PublicKeyParts, RsaPublicKey};
 
+/// Length in bytes of the modulus of the 1024-bit onion keys
+const MODULUS_LEN: usize = 128;
+
 /// Serialize the RsaPublicKey to a big-endian byte array.
 pub fn to_be_bytes(rsa_pub_key: RsaPublicKey) -> Vec<u8> {
     let mut buf = Vec::new();
@@ -9,8 +13,22 @@ pub fn to_be_bytes(rsa_pub_key: RsaPublicKey) -> Vec<u8> {
 }
 
 /// Deserialize the RsaPublicKey from a big-endian byte array.
-pub fn from_be_bytes(buf: &[u8]) -> RsaPublicKey {
-    let n = BigUint::from_bytes_be(&buf[..128]);
-    let e = BigUint::from_bytes_be(&buf[128..]);
-    RsaPublicKey::new(n, e).unwrap()
+pub fn from_be_bytes(buf: &[u8]) -> Result<RsaPublicKey, OnionError> {
+    if buf.len() <= MODULUS_LEN {
+        return Err(OnionError::Truncated {
+            what: "RSA public key",
+            expected: MODULUS_LEN + 1,
+            actual: buf.len(),
+        });
+    }
+    let n = BigUint::from_bytes_be(&buf[..MODULUS_LEN]);
+    // A leading zero byte means the modulus is shorter than an onion key (or zero)
+    if buf[0] == 0 {
+        return Err(OnionError::InvalidModulus {
+            bits: n.bits(),
+            expected: MODULUS_LEN * 8,
+        });
+    }
+    let e = BigUint::from_bytes_be(&buf[MODULUS_LEN..]);
+    Ok(RsaPublicKey::new(n, e)?)
 }
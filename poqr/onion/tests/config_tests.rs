@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod config_tests {
+    use onion::{Directory, Host, OnionConfig, OnionError, DEFAULT_CIRCUIT_LENGTH};
+    use std::path::PathBuf;
+    use std::sync::{Arc, RwLock};
+
+    /// Assert that parsing fails on the given 1-based line
+    fn assert_invalid_line(text: &str, expected_line: usize) {
+        match OnionConfig::parse(text) {
+            Err(OnionError::InvalidConfigLine { line, .. }) => {
+                assert_eq!(line, expected_line, "Error reported on the wrong line")
+            }
+            other => panic!("Expected an error on line {expected_line}, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let text = "# relay config\n\nport 9001\n  id-key keys/relay.ntru  \ncircuit-length 5\n";
+        let config = OnionConfig::parse(text).expect("Config should parse");
+        assert_eq!(
+            config,
+            OnionConfig {
+                port: 9001,
+                id_key_path: Some(PathBuf::from("keys/relay.ntru")),
+                circuit_length: 5,
+            }
+        );
+
+        // Optional directives fall back to their defaults
+        let config = OnionConfig::parse("port 9001").expect("Config should parse");
+        assert_eq!(config.id_key_path, None);
+        assert_eq!(config.circuit_length, DEFAULT_CIRCUIT_LENGTH);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(
+            matches!(
+                OnionConfig::parse("# no port\ncircuit-length 3\n"),
+                Err(OnionError::MissingConfigField("port"))
+            ),
+            "Config without a port should be rejected"
+        );
+
+        // Comments and blank lines still count towards the reported line number
+        assert_invalid_line("# comment\n\nport 9001\nport 70000\n", 4);
+        assert_invalid_line("port abc", 1);
+        assert_invalid_line("port 9001\ncircuit-length 0", 2);
+        assert_invalid_line("port 9001\ncircuit-length -1", 2);
+        assert_invalid_line("port 9001\nexit-policy accept 80", 2);
+        assert_invalid_line("port 9001 9002", 1);
+        assert_invalid_line("port 9001\nid-key", 2);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let dir = std::env::temp_dir().join(format!("poqr-config-tests-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let key_path = dir.join("relay.ntru");
+        let config = OnionConfig {
+            port: 9001,
+            id_key_path: Some(key_path.clone()),
+            circuit_length: 2,
+        };
+        let directory = Arc::new(RwLock::new(Directory::new()));
+
+        // The relay uses the configured port and saves its identity key on first start
+        let id = Directory::generate_relay_from_config(directory.clone(), &config)
+            .expect("Relay should be created");
+        assert!(key_path.exists(), "Identity key should be saved");
+        let relay = directory
+            .read()
+            .unwrap()
+            .get_relay_info(id)
+            .unwrap()
+            .clone();
+        assert_eq!(relay.port, 9001);
+
+        // A second relay can't reuse the port
+        assert!(
+            matches!(
+                Directory::generate_relay_from_config(directory.clone(), &config),
+                Err(OnionError::PortInUse(9001))
+            ),
+            "Duplicate relay port should be rejected"
+        );
+
+        // A host loading the same key file gets the same identity
+        let host = Host::from_config(&config, directory).expect("Host should be created");
+        assert_eq!(host.circuit_length, 2);
+        assert_eq!(
+            host.id_key.public.to_be_bytes(),
+            relay.id_key_pub.to_be_bytes(),
+            "Host should load the saved identity key"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
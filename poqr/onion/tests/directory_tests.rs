@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod directory_tests {
+    use onion::{Directory, RelayId};
+    use std::collections::HashSet;
+    use std::sync::{Arc, RwLock};
+
+    #[test]
+    fn test_get_random_relay() {
+        let directory = Arc::new(RwLock::new(Directory::new()));
+        assert!(
+            directory
+                .read()
+                .unwrap()
+                .get_random_relay(HashSet::new())
+                .is_none(),
+            "Empty directory should have no relays"
+        );
+
+        let ids: Vec<RelayId> = (0..3)
+            .map(|_| Directory::generate_relay(directory.clone()))
+            .collect();
+        let dir = directory.read().unwrap();
+        assert_eq!(dir.num_relays(), 3);
+
+        // With all but one relay excluded, only that relay can be picked
+        let exclude_list: HashSet<RelayId> = ids[1..].iter().copied().collect();
+        for _ in 0..10 {
+            let relay = dir
+                .get_random_relay(exclude_list.clone())
+                .expect("One relay should remain");
+            assert_eq!(relay.id, ids[0], "Picked an excluded relay");
+        }
+
+        // With every relay excluded, there is nothing left to pick
+        let exclude_list: HashSet<RelayId> = ids.iter().copied().collect();
+        assert!(
+            dir.get_random_relay(exclude_list).is_none(),
+            "Should return None when every relay is excluded"
+        );
+    }
+}
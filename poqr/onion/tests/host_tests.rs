@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod host_tests {
+    use ntru::NtruKeyPair;
+    use onion::{Directory, Host, OnionError};
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::sync::{mpsc, Arc, RwLock};
+    use std::time::Duration;
+
+    #[test]
+    fn test_create_circuit_returns() {
+        let directory = Arc::new(RwLock::new(Directory::new()));
+        // Relays only listen once started, so connecting to this one is refused
+        Directory::generate_relay(directory.clone());
+        let mut host = Host::with_seed(9000, directory, 3973);
+        host.circuit_length = 1;
+
+        // Run on another thread so a deadlock fails the test instead of hanging it
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || sender.send(host.create_circuit(80)).unwrap());
+        let result = receiver
+            .recv_timeout(Duration::from_secs(60))
+            .expect("create_circuit deadlocked");
+        assert!(
+            matches!(result, Err(OnionError::Io(_))),
+            "Connecting to a relay that isn't listening should fail"
+        );
+    }
+
+    #[test]
+    fn test_list_relays() {
+        let directory = Arc::new(RwLock::new(Directory::new()));
+        let host = Host::new(9000, directory.clone());
+        assert!(
+            host.list_relays().is_empty(),
+            "Directory should start empty"
+        );
+
+        let ids: Vec<u32> = (0..3)
+            .map(|_| Directory::generate_relay(directory.clone()))
+            .collect();
+        let relays = host.list_relays();
+        let listed: Vec<u32> = relays.iter().map(|relay| relay.id).collect();
+        assert_eq!(listed, ids, "Relays should be listed by ID");
+
+        let dir = directory.read().unwrap();
+        for relay in &relays {
+            assert_eq!(relay.port, dir.get_relay_info(relay.id).unwrap().port);
+        }
+    }
+
+    #[test]
+    fn test_list_and_destroy_circuits() {
+        let directory = Arc::new(RwLock::new(Directory::new()));
+        let mut host = Host::new(9000, directory);
+        assert!(host.list_circuits().is_empty());
+
+        // Stand in for the first relay of circuit 7 so the host has a channel to close
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        host.create_channel(7, port, NtruKeyPair::new().public, Vec::new())
+            .expect("Channel should connect");
+        let (mut relay_end, _) = listener.accept().unwrap();
+        {
+            let mut circuits = host.circuit_table.lock().unwrap();
+            circuits.insert(8080, 7);
+            circuits.insert(80, 9);
+        }
+        assert_eq!(host.list_circuits(), [(80, 9), (8080, 7)]);
+
+        host.destroy_circuit(7).expect("Circuit 7 should exist");
+        assert_eq!(host.list_circuits(), [(80, 9)]);
+        assert!(
+            !host.channels.lock().unwrap().contains_key(7),
+            "Channel should be removed"
+        );
+        let mut buf = [0u8; 1];
+        assert_eq!(
+            relay_end.read(&mut buf).unwrap(),
+            0,
+            "Relay should see the connection close"
+        );
+
+        assert!(
+            matches!(host.destroy_circuit(7), Err(OnionError::UnknownCircuit(7))),
+            "Destroying a circuit twice should fail"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+#[cfg(test)]
+mod keystore_tests {
+    use ntru::NtruPublicKey;
+    use onion::{fingerprint, load_key, KeyStore, OnionError, IDENTITY_KEY};
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// A fresh, empty directory for one test's key store
+    fn temp_store_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("poqr-keystore-{test}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_load_or_generate() {
+        let dir = temp_store_dir("load");
+        let store = KeyStore::open(&dir).expect("Store should open");
+        assert!(dir.is_dir(), "Opening should create the store directory");
+        assert!(
+            store.load(IDENTITY_KEY).unwrap().is_none(),
+            "Empty store should have no identity key"
+        );
+
+        // The first call generates and saves a key, later calls load the same one
+        let generated = store.load_or_generate(IDENTITY_KEY).unwrap();
+        let loaded = store
+            .load(IDENTITY_KEY)
+            .unwrap()
+            .expect("Key should be saved");
+        assert_eq!(
+            loaded.public.to_be_bytes(),
+            generated.public.to_be_bytes(),
+            "Loaded key should match the generated one"
+        );
+        let reloaded = store.load_or_generate(IDENTITY_KEY).unwrap();
+        assert_eq!(
+            reloaded.public.to_be_bytes(),
+            generated.public.to_be_bytes(),
+            "load_or_generate should not replace an existing key"
+        );
+
+        // A reloaded key still decrypts messages sent to the original
+        let msg = vec![1, 2, 3, 241];
+        let enc = generated.public.encrypt_bytes(msg.clone());
+        assert_eq!(loaded.private.decrypt_to_bytes(enc), msg);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rotate() {
+        let dir = temp_store_dir("rotate");
+        let store = KeyStore::open(&dir).unwrap();
+        let old = store.load_or_generate(IDENTITY_KEY).unwrap();
+
+        let new = store.rotate(IDENTITY_KEY).unwrap();
+        assert_ne!(
+            fingerprint(&new.public),
+            fingerprint(&old.public),
+            "Rotation should generate a new key"
+        );
+
+        let old_path = dir.join("identity.ntru.old");
+        let kept = load_key(&old_path).expect("Previous key should be kept");
+        assert_eq!(fingerprint(&kept.public), fingerprint(&old.public));
+        let current = store.load(IDENTITY_KEY).unwrap().unwrap();
+        assert_eq!(fingerprint(&current.public), fingerprint(&new.public));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_rotate_keeps_key() {
+        let dir = temp_store_dir("failed-rotate");
+        let store = KeyStore::open(&dir).unwrap();
+        let current = store.load_or_generate(IDENTITY_KEY).unwrap();
+
+        // A directory in the way of the temporary key file makes saving the new key fail
+        fs::create_dir(dir.join("identity.ntru.tmp")).unwrap();
+        assert!(
+            store.rotate(IDENTITY_KEY).is_err(),
+            "Rotation should fail when the new key can't be saved"
+        );
+        let kept = store
+            .load(IDENTITY_KEY)
+            .unwrap()
+            .expect("Current key should survive a failed rotation");
+        assert_eq!(fingerprint(&kept.public), fingerprint(&current.public));
+        assert!(
+            !dir.join("identity.ntru.old").exists(),
+            "Current key shouldn't be moved aside"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_key_names() {
+        let dir = temp_store_dir("names");
+        let store = KeyStore::open(&dir).unwrap();
+
+        for name in ["", "..", "../escaped", "sub/key", "a..b"] {
+            assert!(
+                matches!(store.path(name), Err(OnionError::InvalidKeyName(_))),
+                "{name:?} should be rejected"
+            );
+            assert!(matches!(
+                store.load_or_generate(name),
+                Err(OnionError::InvalidKeyName(_))
+            ));
+            assert!(matches!(
+                store.rotate(name),
+                Err(OnionError::InvalidKeyName(_))
+            ));
+        }
+        assert!(
+            !dir.with_file_name("escaped.ntru").exists(),
+            "No key should be written outside the store"
+        );
+        assert_eq!(
+            store.path("relay-1").unwrap(),
+            dir.join("relay-1.ntru"),
+            "Plain names should map into the store"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_list() {
+        let dir = temp_store_dir("list");
+        let store = KeyStore::open(&dir).unwrap();
+        let b = store.load_or_generate("b").unwrap();
+        let a = store.load_or_generate("a").unwrap();
+        store.rotate("a").unwrap();
+        // Files that aren't loadable keys are skipped
+        fs::write(dir.join("junk.ntru"), [1, 2, 3]).unwrap();
+        fs::write(dir.join("notes.txt"), "not a key").unwrap();
+        // Including a key whose g(x) has a coefficient too large to be an NTRU key
+        let mut bytes = a.private.to_be_bytes();
+        let f_len = u32::from_be_bytes(bytes[..4].try_into().unwrap()) as usize;
+        let g_start = 4 + f_len + 4;
+        bytes[g_start..g_start + 4].copy_from_slice(&(1i32 << 30).to_be_bytes());
+        fs::write(dir.join("huge.ntru"), bytes).unwrap();
+
+        let keys = store.list().expect("Listing should succeed");
+        let names: Vec<&str> = keys.iter().map(|key| key.name.as_str()).collect();
+        assert_eq!(names, ["a", "b"], "Keys should be listed by name");
+        assert_ne!(
+            keys[0].fingerprint,
+            fingerprint(&a.public),
+            "Listing should show the rotated key"
+        );
+        assert_eq!(keys[1].fingerprint, fingerprint(&b.public));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let dir = temp_store_dir("fingerprint");
+        let store = KeyStore::open(&dir).unwrap();
+        let key = store.load_or_generate(IDENTITY_KEY).unwrap();
+
+        let print = fingerprint(&key.public);
+        assert_eq!(
+            print.len(),
+            64,
+            "SHA-256 fingerprint should be 64 hex digits"
+        );
+        assert!(print
+            .chars()
+            .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c)));
+        assert_eq!(
+            fingerprint(&store.load(IDENTITY_KEY).unwrap().unwrap().public),
+            print,
+            "Fingerprint should survive a reload"
+        );
+
+        // A peer that receives the key over the wire must compute the same fingerprint
+        let received = NtruPublicKey::from_be_bytes(&key.public.to_be_bytes()).unwrap();
+        assert_eq!(
+            fingerprint(&received),
+            print,
+            "Fingerprint should survive a wire round trip"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_store_dir("permissions");
+        let store = KeyStore::open(&dir).unwrap();
+        store.load_or_generate(IDENTITY_KEY).unwrap();
+        store.rotate(IDENTITY_KEY).unwrap();
+
+        for file in ["identity.ntru", "identity.ntru.old"] {
+            let mode = fs::metadata(dir.join(file)).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600, "{file} should be owner-only");
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod message_tests {
+    use ntru::{NtruError, NtruKeyPair};
+    use onion::{
+        DataPayload, Directory, Host, Message, OnionError, OnionPacket, RelayPayload,
+        MAX_MESSAGE_LEN,
+    };
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::sync::{Arc, RwLock};
+
+    fn data_message(data: &[u8]) -> Message {
+        Message::Relay(RelayPayload::Data(DataPayload::from_be_bytes(data)))
+    }
+
+    #[test]
+    fn test_unencodable_message() {
+        let id_key = NtruKeyPair::new();
+
+        // Messages that don't fit in one NTRU message return errors instead of panicking
+        assert!(
+            matches!(
+                data_message(&[1; 200]).to_be_bytes(id_key.public.clone(), Vec::new()),
+                Err(OnionError::Ntru(NtruError::PlaintextTooLong { .. }))
+            ),
+            "Oversized message should be rejected"
+        );
+        assert!(
+            matches!(
+                data_message(&[1, 250, 3]).to_be_bytes(id_key.public.clone(), Vec::new()),
+                Err(OnionError::Ntru(NtruError::UnencodableByte(250)))
+            ),
+            "Message with a byte above 241 should be rejected"
+        );
+
+        assert!(
+            data_message(&[1, 241, 3])
+                .to_be_bytes(id_key.public, Vec::new())
+                .is_ok(),
+            "Encodable message should serialize"
+        );
+    }
+
+    #[test]
+    fn test_oversized_packet() {
+        let id_key = NtruKeyPair::new();
+        // Circuit 7, followed by a message length just past the largest NTRU ciphertext
+        let mut header = vec![0, 0, 0, 7];
+        header.extend_from_slice(&(MAX_MESSAGE_LEN as u32 + 1).to_be_bytes());
+
+        assert!(
+            matches!(
+                OnionPacket::from_be_bytes(&header, id_key.private.clone(), Vec::new()),
+                Err(OnionError::MessageTooLong { .. })
+            ),
+            "Packet decoding should reject oversized lengths"
+        );
+
+        // A channel rejects the length before trying to read or allocate the message
+        let host = Host::new(9000, Arc::new(RwLock::new(Directory::new())));
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        host.create_channel(7, port, id_key.public, Vec::new())
+            .expect("Channel should connect");
+        let (mut peer, _) = listener.accept().unwrap();
+        peer.write_all(&header).unwrap();
+
+        let mut channel = host.channels.lock().unwrap().get(7).unwrap().clone();
+        assert!(
+            matches!(channel.recv(), Err(OnionError::MessageTooLong { .. })),
+            "Channel should reject oversized lengths"
+        );
+    }
+}
@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod rsa_utils_tests {
+    use onion::{from_be_bytes, to_be_bytes, OnionError};
+    use rsa_ext::{RsaPrivateKey, RsaPublicKey};
+
+    #[test]
+    fn test_bytecode() {
+        let private_key =
+            RsaPrivateKey::new(&mut rand::thread_rng(), 1024).expect("Key generation failed");
+        let public_key = RsaPublicKey::from(&private_key);
+
+        let decoded = from_be_bytes(&to_be_bytes(public_key.clone())).expect("Decoding failed");
+        assert!(decoded == public_key, "Failed encoding RSA public key");
+    }
+
+    #[test]
+    fn test_invalid_modulus() {
+        // A zero modulus followed by e = 3
+        let mut buf = vec![0u8; 128];
+        buf.push(3);
+        assert!(
+            matches!(
+                from_be_bytes(&buf),
+                Err(OnionError::InvalidModulus {
+                    bits: 0,
+                    expected: 1024
+                })
+            ),
+            "Zero modulus should be rejected"
+        );
+
+        // A modulus one byte short of a 1024-bit key
+        buf[0] = 0;
+        buf[1] = 0xff;
+        assert!(
+            matches!(
+                from_be_bytes(&buf),
+                Err(OnionError::InvalidModulus { bits: 1016, .. })
+            ),
+            "Short modulus should be rejected"
+        );
+
+        // Buffers with no room for the exponent are rejected
+        assert!(
+            matches!(
+                from_be_bytes(&buf[..128]),
+                Err(OnionError::Truncated { .. })
+            ),
+            "Truncated key should be rejected"
+        );
+    }
+}
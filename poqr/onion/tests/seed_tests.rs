@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod seed_tests {
+    use onion::{Directory, Host, RelayId};
+    use std::collections::HashSet;
+    use std::sync::{Arc, RwLock};
+
+    const SEED: u64 = 3973;
+    const NUM_RELAYS: usize = 4;
+    const NUM_PICKS: usize = 20;
+
+    fn seeded_directory(seed: u64) -> Arc<RwLock<Directory>> {
+        let directory = Arc::new(RwLock::new(Directory::with_seed(seed)));
+        for _ in 0..NUM_RELAYS {
+            Directory::generate_relay(directory.clone());
+        }
+        directory
+    }
+
+    fn relay_picks(directory: &Arc<RwLock<Directory>>) -> Vec<RelayId> {
+        let dir = directory.read().unwrap();
+        (0..NUM_PICKS)
+            .map(|_| {
+                dir.get_random_relay(HashSet::new())
+                    .expect("Directory should have relays")
+                    .id
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_directory_seed() {
+        let first = relay_picks(&seeded_directory(SEED));
+        let second = relay_picks(&seeded_directory(SEED));
+        assert_eq!(
+            first, second,
+            "Directories with the same seed should pick the same relays"
+        );
+    }
+
+    #[test]
+    fn test_host_seed() {
+        let directory = Arc::new(RwLock::new(Directory::new()));
+        let first = Host::with_seed(9000, directory.clone(), SEED);
+        let second = Host::with_seed(9001, directory, SEED);
+
+        for _ in 0..NUM_PICKS {
+            assert_eq!(
+                first.generate_new_circuit_id(),
+                second.generate_new_circuit_id(),
+                "Hosts with the same seed should generate the same circuit IDs"
+            );
+        }
+    }
+}